@@ -0,0 +1,59 @@
+use crate::common::account::order::Order;
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::message::message::Message;
+use crate::common::message::request::{AmendOrderRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Amends the price or quantity of an open order on the connected server.
+///
+/// Terms left to ```None``` are not changed. The quantity counts the shares filled already.
+/// The server fills the amended order at once as far as the latest quote of the stock allows.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+/// order_id - The id of the order to amend.
+/// quantity - The new number of shares of the order.
+/// limit_price - The new limit price, for limit and stop-limit orders.
+/// stop_price - The new stop price, for stop and stop-limit orders.
+///
+/// Returns: the amended ```Order```, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let order = acc_amend_order(&dispatcher, auth_jwt, order.id, None, Some(151.0), None).await?;
+/// ```
+pub async fn acc_amend_order(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+    order_id: i64,
+    quantity: Option<i64>,
+    limit_price: Option<f64>,
+    stop_price: Option<f64>,
+) -> Result<Order, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let request = Request::AmendOrder(AmendOrderRequest {
+        auth_jwt,
+        order_id,
+        quantity,
+        limit_price,
+        stop_price,
+    });
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::OrderAmended(order)) => Ok(order),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientAmendOrderFailed,
+        )),
+    }
+}
@@ -1,20 +1,15 @@
-use data_encoding::HEXUPPER;
 use ring::digest;
-use std::io;
 
 use crate::common::account::hash::hash;
 use crate::common::message::inst::CommandInst;
 use crate::common::message::message::Message;
-use crate::common::message::message_builder::message_builder;
-use crate::common::message::message_type::MessageType;
-use crate::common::misc::assert_msg::assert_msg;
+use crate::common::message::request::{LoginRequest, Request};
+use crate::common::message::response::Response;
 use crate::common::misc::return_flags::ReturnFlags;
 
+use crate::client::network::client_error::ClientError;
 use crate::client::network::cmd::req_server_salt::req_server_salt;
-
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tokio_rustls::client::TlsStream;
+use crate::client::network::dispatcher::Dispatcher;
 
 /// Client authentication procedure.
 ///
@@ -25,37 +20,38 @@ use tokio_rustls::client::TlsStream;
 /// Should be used in Async contexts.
 ///
 /// Arguments:
-/// socket - TLS socket to use.
+/// dispatcher - The dispatcher of the connection to use.
 /// username - The raw username to be used.
 /// email - The raw email to be used.
 /// password - The raw password to be used.
 ///
-/// Returns: ```io::Result``` wraps JWT token on succes, and contains error message on failure.
+/// Returns: the JWT token on success, ```ClientError``` on failure.
 ///
 /// Example:
 /// ```rust
-///     match acc_auth(&mut socket, "username", "email", "password").await {
+///     match acc_auth(&dispatcher, "username", "email", "password").await {
 ///         Ok(jwt) => { /* use JWT token */ }
-///         Err(err) => panic!("panik! {}", err), /* unauth OR invalid JWT token */
+///         Err(ClientError::Server(ReturnFlags::ServerAccUnauthorized, _)) => { /* unauth */ }
+///         Err(err) => panic!("panik! {}", err), /* invalid JWT token */
 ///     }
 /// ```
 pub async fn acc_auth(
-    socket: &mut TlsStream<TcpStream>,
+    dispatcher: &Dispatcher,
     username: &str,
     email: &str,
     password: &str,
-) -> io::Result<String> {
+) -> Result<String, ClientError> {
     /*
      * get email salt
      * */
     let email_salt: [u8; digest::SHA512_OUTPUT_LEN] =
-        req_server_salt(socket, username, CommandInst::GetEmailSalt as i64).await?;
+        req_server_salt(dispatcher, username, CommandInst::GetEmailSalt as i64).await?;
 
     /*
      * get password salt
      * */
     let password_salt: [u8; digest::SHA512_OUTPUT_LEN] =
-        req_server_salt(socket, username, CommandInst::GetPasswordSalt as i64).await?;
+        req_server_salt(dispatcher, username, CommandInst::GetPasswordSalt as i64).await?;
 
     /*
      * hash the email
@@ -71,59 +67,27 @@ pub async fn acc_auth(
         250_000,
     );
 
-    /* generate message to be sent to the server */
-    let data = object! {
-        hashed_email: HEXUPPER.encode(&hashed_email),
-        hashed_password: HEXUPPER.encode(&hashed_password),
-        username: username
-    };
-    let message = message_builder(
-        MessageType::Command,
-        CommandInst::LoginMethod1 as i64,
-        3,
-        0,
-        0,
-        data.dump().as_bytes().to_vec(),
-    );
-    socket
-        .write_all(&bincode::serialize(&message).unwrap())
-        .await?;
+    /* generate request to be sent to the server */
+    let request = Request::LoginNormal(LoginRequest {
+        username: username.to_string(),
+        hashed_email: hashed_email.to_vec(),
+        hashed_password: hashed_password.to_vec(),
+    });
 
     /* decode response */
-    let mut buf = Vec::with_capacity(4096);
-    socket.read_buf(&mut buf).await?;
-    let response: Message = bincode::deserialize(&buf).map_err(|_| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("{}", ReturnFlags::ClientAccUnauthorized),
-        )
-    })?;
+    let response: Message = dispatcher.request(request.to_message()).await?;
 
-    if assert_msg(
-        &response,
-        MessageType::ServerReturn,
-        true,
-        1,
-        false,
-        0,
-        false,
-        0,
-        false,
-        0,
-    ) && response.data.len() != 0
-        && response.instruction == 1
-    {
-        /* authorized */
-        return Ok(String::from_utf8(response.data).map_err(|_| {
-            io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("{}", ReturnFlags::ClientAccInvalidSessionId),
-            )
-        })?);
-    } else {
-        return Err(io::Error::new(
-            io::ErrorKind::ConnectionRefused,
-            format!("{}", ReturnFlags::ClientAccUnauthorized),
-        ));
+    match Response::from_message(&response) {
+        Ok(Response::LoggedIn(jwt)) if !jwt.is_empty() => {
+            /* authorized */
+            Ok(jwt)
+        }
+        Ok(Response::LoggedIn(_)) => {
+            Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId))
+        }
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientAccUnauthorized,
+        )),
     }
 }
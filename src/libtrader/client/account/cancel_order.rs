@@ -0,0 +1,46 @@
+use crate::common::account::order::Order;
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::message::message::Message;
+use crate::common::message::request::{CancelOrderRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Cancels an open or pending order on the connected server.
+///
+/// The order keeps the shares it filled already.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+/// order_id - The id of the order to cancel.
+///
+/// Returns: the cancelled ```Order```, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let order = acc_cancel_order(&dispatcher, auth_jwt, order.id).await?;
+/// ```
+pub async fn acc_cancel_order(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+    order_id: i64,
+) -> Result<Order, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let request = Request::CancelOrder(CancelOrderRequest { auth_jwt, order_id });
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::OrderCancelled(order)) => Ok(order),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientCancelOrderFailed,
+        )),
+    }
+}
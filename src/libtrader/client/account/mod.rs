@@ -1,6 +1,13 @@
+pub mod amend_order;
 pub mod authorization;
+pub mod cancel_order;
 pub mod creation;
 pub mod hash_email;
 pub mod hash_pwd;
+pub mod place_order;
+pub mod place_order_group;
+pub mod purchase_asset;
+pub mod retrieval_orders;
 pub mod retrieval_portfolio;
 pub mod retrieval_transaction;
+pub mod sell_asset;
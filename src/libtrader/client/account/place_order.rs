@@ -0,0 +1,66 @@
+use crate::common::account::order::Order;
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::message::message::Message;
+use crate::common::message::request::{PlaceOrderRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Places an order on the connected server.
+///
+/// The terms of the order are taken from ```order```: its side, symbol, type, amount, time in
+/// force and the prices its type uses. The server fills the order at once as far as the latest quote of the
+/// stock allows, the rest is triggered and filled as later quotes come.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+/// order - The terms of the order, its other members are ignored.
+///
+/// Returns: the ```Order``` as placed, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let stop = Order {
+///         stock_symbol: "AAPL".to_string(),
+///         order_type: OrderType::TrailingStop,
+///         stock_amount: 10,
+///         trail_amount: 5.0,
+///         ..Order::default()
+///     };
+///     let order = acc_place_order(&dispatcher, auth_jwt, &stop).await?;
+///     println!("order {} stops at {}", order.id, order.stop_price);
+/// ```
+pub async fn acc_place_order(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+    order: &Order,
+) -> Result<Order, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let request = Request::PlaceOrder(PlaceOrderRequest {
+        auth_jwt,
+        symbol: order.stock_symbol.clone(),
+        is_buy: order.is_buy,
+        order_type: order.order_type,
+        quantity: order.stock_amount,
+        limit_price: order.stock_price,
+        stop_price: order.stop_price,
+        trail_amount: order.trail_amount,
+        time_in_force: order.time_in_force,
+    });
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::OrderPlaced(order)) => Ok(order),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientPlaceOrderFailed,
+        )),
+    }
+}
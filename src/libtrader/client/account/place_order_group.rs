@@ -0,0 +1,56 @@
+use crate::common::account::order::Order;
+use crate::common::account::order_group::{GroupType, OrderGroup};
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::message::message::Message;
+use crate::common::message::request::{PlaceOrderGroupRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Places linked orders on the connected server.
+///
+/// An OCO group takes two legs, a bracket takes a buy entry, a limit take-profit and a stop
+/// loss. The exits of a bracket sell the shares the entry buys, their amount is ignored.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+/// group_type - The kind of the group.
+/// orders - The terms of the orders, see ```acc_place_order()```.
+///
+/// Returns: the ```OrderGroup``` as placed, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let group = acc_place_order_group(&dispatcher, auth_jwt, GroupType::Bracket,
+///         vec![entry, take_profit, stop_loss]).await?;
+///     println!("bracket {} placed", group.id);
+/// ```
+pub async fn acc_place_order_group(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+    group_type: GroupType,
+    orders: Vec<Order>,
+) -> Result<OrderGroup, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let request = Request::PlaceOrderGroup(PlaceOrderGroupRequest {
+        auth_jwt,
+        group_type,
+        orders,
+    });
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::OrderGroupPlaced(group)) => Ok(group),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientPlaceOrderGroupFailed,
+        )),
+    }
+}
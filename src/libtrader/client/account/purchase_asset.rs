@@ -0,0 +1,52 @@
+use crate::common::account::fill::Fill;
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::message::message::Message;
+use crate::common::message::request::{PurchaseAssetRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Buys shares of a stock at the market price on the connected server.
+///
+/// The server fills the order at once, at the latest ask of the stock.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+/// symbol - The symbol of the stock to buy.
+/// quantity - The number of shares to buy.
+///
+/// Returns: the ```Fill``` of the order, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let fill = acc_purchase_asset(&dispatcher, auth_jwt, "AAPL", 10).await?;
+/// ```
+pub async fn acc_purchase_asset(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+    symbol: &str,
+    quantity: i64,
+) -> Result<Fill, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let request = Request::PurchaseAsset(PurchaseAssetRequest {
+        auth_jwt,
+        symbol: symbol.to_string(),
+        quantity,
+    });
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::AssetPurchased(fill)) => Ok(fill),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientPurchaseAssetFailed,
+        )),
+    }
+}
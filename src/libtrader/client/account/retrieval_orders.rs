@@ -0,0 +1,83 @@
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::account::order::Order;
+use crate::common::message::message::Message;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Retrieves from the connected server the open orders of an authorized account.
+///
+/// Pending exits of a bracket are listed along with the open orders.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+///
+/// Returns: the orders, oldest first, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     for order in acc_retrieve_open_orders(&dispatcher, auth_jwt).await? {
+///         println!("{}: {}/{}", order.id, order.stock_filled, order.stock_amount);
+///     }
+/// ```
+pub async fn acc_retrieve_open_orders(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+) -> Result<Vec<Order>, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let response: Message = dispatcher
+        .request(Request::GetUserOpenOrders(auth_jwt).to_message())
+        .await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::OpenOrders(orders)) => Ok(orders),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientRetrieveOrdersFailed,
+        )),
+    }
+}
+
+/// Retrieves from the connected server the order history of an authorized account.
+///
+/// The history holds the orders that were filled, rejected, cancelled or expired.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+///
+/// Returns: the orders, newest first, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let history = acc_retrieve_order_history(&dispatcher, auth_jwt).await?;
+/// ```
+pub async fn acc_retrieve_order_history(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+) -> Result<Vec<Order>, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let response: Message = dispatcher
+        .request(Request::GetUserOrderHistory(auth_jwt).to_message())
+        .await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::OrderHistory(orders)) => Ok(orders),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientRetrieveOrdersFailed,
+        )),
+    }
+}
@@ -1,17 +1,11 @@
-use std::io;
-
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
 use crate::common::account::portfolio::Portfolio;
-use crate::common::message::inst::DataTransferInst;
 use crate::common::message::message::Message;
-use crate::common::message::message_builder::message_builder;
-use crate::common::message::message_type::MessageType;
-use crate::common::misc::assert_msg::assert_msg;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
 use crate::common::misc::return_flags::ReturnFlags;
 
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tokio_rustls::client::TlsStream;
-
 /// Retrieves from the connected TLS server an authorized portfolio.
 ///
 /// Sends a request for portfolio with the JWT token of the client connection. Handles any response
@@ -20,72 +14,39 @@ use tokio_rustls::client::TlsStream;
 /// Should be used in Async context.
 ///
 /// Arguments:
-/// socket - TLS socket to use.
+/// dispatcher - The dispatcher of the connection to use.
 /// auth_jwt - JWT token to authenticate with.
 ///
-/// Returns: ```io::Result``` wraps ```Portfolio```.
+/// Returns: the ```Portfolio```, ```ClientError``` on failure.
 ///
 /// Example:
 /// ```rust
-///     let mut portfolio = acc_retrieve_portfolio(&mut tls_client, &mut poll)?;
+///     let mut portfolio = acc_retrieve_portfolio(&dispatcher, auth_jwt).await?;
 /// ```
 pub async fn acc_retrieve_portfolio(
-    socket: &mut TlsStream<TcpStream>,
+    dispatcher: &Dispatcher,
     auth_jwt: String,
-) -> io::Result<Portfolio> {
+) -> Result<Portfolio, ClientError> {
     if auth_jwt.is_empty() == true {
-        return Err(io::Error::new(
-            io::ErrorKind::PermissionDenied,
-            "ACC_RETRIEVE_PORTFOLIO: JWT TOKEN EMPTY",
-        ));
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
     }
 
-    /* build message request */
-    let message = message_builder(
-        MessageType::Command,
-        DataTransferInst::GetUserPortfolio as i64,
-        1,
-        0,
-        0,
-        bincode::serialize(&auth_jwt).unwrap(),
-    );
-    socket
-        .write_all(&bincode::serialize(&message).unwrap())
+    /* send request, decode response */
+    let response: Message = dispatcher
+        .request(Request::GetUserPortfolio(auth_jwt).to_message())
         .await?;
 
-    /* decode response */
-    let mut buf = Vec::with_capacity(4096);
-    socket.read_buf(&mut buf).await?;
-
-    let response: Message = bincode::deserialize(&buf).map_err(|_| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!("{}", ReturnFlags::ClientAccRetrievePortfolioError),
-        )
-    })?;
-
-    if assert_msg(
-        &response,
-        MessageType::DataTransfer,
-        true,
-        1,
-        false,
-        0,
-        false,
-        0,
-        false,
-        0,
-    ) && response.instruction == 1
-        && response.data.len() != 0
-    {
-        /* returned data */
-        let portfolio: Portfolio = bincode::deserialize(&response.data).unwrap();
-        return Ok(portfolio);
-    } else {
-        /* could not get data */
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("{}", ReturnFlags::ClientAccRetrievePortfolioError),
-        ));
+    match Response::from_message(&response) {
+        Ok(Response::Portfolio(portfolio)) => {
+            /* returned data */
+            Ok(portfolio)
+        }
+        response => {
+            /* could not get data */
+            Err(ClientError::from_response(
+                response,
+                ReturnFlags::ClientAccRetrievePortfolioError,
+            ))
+        }
     }
 }
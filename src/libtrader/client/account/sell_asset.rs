@@ -0,0 +1,55 @@
+use crate::common::account::fill::Fill;
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+use crate::common::message::message::Message;
+use crate::common::message::request::{Request, SellAssetRequest};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Sells shares of a stock at the market price on the connected server.
+///
+/// The server fills the order at once, at the latest bid of the stock, closing the oldest
+/// positions first.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// auth_jwt - JWT token to authenticate with.
+/// symbol - The symbol of the stock to sell.
+/// quantity - The number of shares to sell.
+///
+/// Returns: the ```Fill``` of the order with its realized profit, ```ClientError``` on failure,
+/// ```ReturnFlags::ServerSellAssetOversold``` if the account holds fewer shares than sold.
+///
+/// Example:
+/// ```rust
+///     let fill = acc_sell_asset(&dispatcher, auth_jwt, "AAPL", 10).await?;
+///     println!("realized {}", fill.realized_pnl);
+/// ```
+pub async fn acc_sell_asset(
+    dispatcher: &Dispatcher,
+    auth_jwt: String,
+    symbol: &str,
+    quantity: i64,
+) -> Result<Fill, ClientError> {
+    if auth_jwt.is_empty() {
+        return Err(ClientError::Client(ReturnFlags::ClientAccInvalidSessionId));
+    }
+
+    /* send request, decode response */
+    let request = Request::SellAsset(SellAssetRequest {
+        auth_jwt,
+        symbol: symbol.to_string(),
+        quantity,
+    });
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::AssetSold(fill)) => Ok(fill),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientSellAssetFailed,
+        )),
+    }
+}
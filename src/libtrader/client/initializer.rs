@@ -4,8 +4,12 @@ use std::net::ToSocketAddrs;
 use tokio::net::TcpStream;
 use tokio_rustls::webpki::DNSNameRef;
 use tokio_rustls::TlsConnector;
+use tokio_util::codec::Framed;
 
+use crate::client::network::cmd::hello::hello;
+use crate::client::network::dispatcher::Dispatcher;
 use crate::client::network::gen_tls_client_config::gen_tls_client_config;
+use crate::common::message::message_codec::MessageCodec;
 
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
@@ -107,7 +111,13 @@ pub async fn libtrader_init_client() -> std::io::Result<()> {
     let domain = DNSNameRef::try_from_ascii_str(&domain)
         .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid dnsname"))?;
 
-    let mut socket = connector.connect(domain, stream).await?;
+    let socket = connector.connect(domain, stream).await?;
+    let mut socket = Framed::new(socket, MessageCodec::new());
+
+    // refuse servers speaking an incompatible protocol
+    let server_hello = hello(&mut socket).await?;
+    println!("connected to {}", server_hello);
+    let dispatcher = Dispatcher::new(socket);
 
     let username: String = thread_rng()
         .sample_iter(&Alphanumeric)
@@ -126,7 +136,7 @@ pub async fn libtrader_init_client() -> std::io::Result<()> {
         .collect();
 
     use crate::client::account::creation::acc_create;
-    match acc_create(&mut socket, &username, &email, &password).await {
+    match acc_create(&dispatcher, &username, &email, &password).await {
         Ok(_) => println!("we created it"),
         Err(err) => panic!("panik! {}", err),
     }
@@ -135,7 +145,7 @@ pub async fn libtrader_init_client() -> std::io::Result<()> {
     let mut jwt: String = String::new();
     println!("{}", jwt); // this is for removing pisky warnings,
                          // this is fine as long as this code is sandbox
-    match acc_auth(&mut socket, &username, &email, &password).await {
+    match acc_auth(&dispatcher, &username, &email, &password).await {
         Ok(auth) => {
             jwt = auth;
             println!("we accessed it, the token: {}", jwt);
@@ -144,13 +154,16 @@ pub async fn libtrader_init_client() -> std::io::Result<()> {
     }
 
     use crate::client::account::retrieval_portfolio::acc_retrieve_portfolio;
-    match acc_retrieve_portfolio(&mut socket, String::from(jwt.as_str())).await {
+    use crate::client::account::retrieval_transaction::acc_retrieve_transaction;
+    let (portfolio, transaction) = tokio::join!(
+        acc_retrieve_portfolio(&dispatcher, String::from(jwt.as_str())),
+        acc_retrieve_transaction(&dispatcher, jwt)
+    );
+    match portfolio {
         Ok(portfolio) => println!("we got portfolio {:#?}", portfolio),
         Err(err) => panic!("panik! {}", err),
     }
-
-    use crate::client::account::retrieval_transaction::acc_retrieve_transaction;
-    match acc_retrieve_transaction(&mut socket, jwt).await {
+    match transaction {
         Ok(transaction) => println!("we got the transactions {:#?}", transaction),
         Err(err) => panic!("panik! {}", err),
     }
@@ -0,0 +1,119 @@
+use std::io;
+
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Errors returned by the client library.
+///
+/// Variants:
+/// Io - The connection to the server failed.
+/// Server - The server refused the request, with the ```ReturnFlags``` it answered and a detail
+/// message.
+/// Client - The client library rejected the request or could not understand the response.
+///
+/// Example:
+/// ```rust
+///     match acc_auth(&dispatcher, "username", "email", "password").await {
+///         Ok(jwt) => { /* use JWT token */ }
+///         Err(ClientError::Server(ReturnFlags::ServerAccUnauthorized, _)) => { /* bad creds */ }
+///         Err(err) => panic!("panik! {}", err),
+///     }
+/// ```
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    Server(ReturnFlags, String),
+    Client(ReturnFlags),
+}
+
+impl ClientError {
+    /// Returns the ```ReturnFlags``` describing the error, if any.
+    pub fn flag(&self) -> Option<ReturnFlags> {
+        match self {
+            ClientError::Io(_) => None,
+            ClientError::Server(flag, _) | ClientError::Client(flag) => Some(*flag),
+        }
+    }
+
+    /// Builds the error for an unexpected response.
+    ///
+    /// Server errors are kept as is, any other response becomes ```ClientError::Client``` with
+    /// ```flag```.
+    ///
+    /// Arguments:
+    /// response - The decoded response, or the error from decoding it.
+    /// flag - The flag to use if the response is not a server error.
+    pub fn from_response(response: Result<Response, ReturnFlags>, flag: ReturnFlags) -> Self {
+        match response {
+            Ok(Response::Error(server_flag, detail)) => ClientError::Server(server_flag, detail),
+            _ => ClientError::Client(flag),
+        }
+    }
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Io(err) => write!(f, "{}", err),
+            ClientError::Server(flag, detail) => write!(f, "{}: {}", flag, detail),
+            ClientError::Client(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+impl From<ClientError> for io::Error {
+    fn from(err: ClientError) -> Self {
+        match err {
+            ClientError::Io(err) => err,
+            ClientError::Server(_, _) => io::Error::new(io::ErrorKind::ConnectionRefused, err),
+            ClientError::Client(_) => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_client_error_from_response() {
+        let err = ClientError::from_response(
+            Ok(Response::Error(
+                ReturnFlags::ServerAccUnauthorized,
+                "could not log in".to_string(),
+            )),
+            ReturnFlags::ClientAccUnauthorized,
+        );
+        assert!(matches!(
+            err,
+            ClientError::Server(ReturnFlags::ServerAccUnauthorized, _)
+        ));
+        assert_eq!(
+            format!("{}", err),
+            "ServerAccUnauthorized: could not log in"
+        );
+
+        let err = ClientError::from_response(
+            Ok(Response::Registered),
+            ReturnFlags::ClientAccUnauthorized,
+        );
+        assert_eq!(err.flag(), Some(ReturnFlags::ClientAccUnauthorized));
+
+        let err = ClientError::from_response(
+            Err(ReturnFlags::CommonMsgDecodeFailed),
+            ReturnFlags::ClientAccUnauthorized,
+        );
+        assert!(matches!(
+            err,
+            ClientError::Client(ReturnFlags::ClientAccUnauthorized)
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use std::io;
+
+use crate::common::message::hello::Hello;
+use crate::common::message::message::Message;
+use crate::common::message::message_codec::MessageCodec;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+use crate::common::network::transport::Transport;
+
+use crate::client::network::client_error::ClientError;
+
+use futures::{SinkExt, StreamExt};
+use tokio_util::codec::Framed;
+
+/// Runs the client side of the hello exchange.
+///
+/// Sends the client hello and waits for the server hello. Must be called before any other
+/// request on a new connection.
+/// Should be used in contexts that return ```io::Result```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// socket - The socket to use.
+///
+/// Returns: the server ```Hello```, fails with ```ReturnFlags::CommonProtocolVersionMismatch```
+/// if the server speaks an incompatible protocol.
+///
+/// Example:
+/// ```rust
+///     let server_hello = hello(&mut socket).await?;
+/// ```
+pub async fn hello<T: Transport>(
+    socket: &mut Framed<T, MessageCodec>,
+) -> Result<Hello, ClientError> {
+    let client_hello = Hello::new();
+    socket
+        .send(Request::Hello(client_hello.clone()).to_message())
+        .await?;
+
+    /* decode response */
+    let response: Message = match socket.next().await {
+        Some(msg) => msg?,
+        None => {
+            return Err(ClientError::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{}", ReturnFlags::ClientHelloFailed),
+            )))
+        }
+    };
+    match Response::from_message(&response) {
+        Ok(Response::Hello(server_hello)) => {
+            if client_hello.is_compatible(&server_hello) {
+                Ok(server_hello)
+            } else {
+                Err(ClientError::Client(
+                    ReturnFlags::CommonProtocolVersionMismatch,
+                ))
+            }
+        }
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientHelloFailed,
+        )),
+    }
+}
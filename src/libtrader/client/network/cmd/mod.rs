@@ -1,2 +1,4 @@
 pub mod get_server_salt;
+pub mod hello;
 pub mod req_server_salt;
+pub mod subscribe;
@@ -0,0 +1,117 @@
+use log::warn;
+use std::io;
+use tokio::sync::broadcast;
+
+use crate::common::generic::stock_val::StockVal;
+use crate::common::message::message::Message;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::client::network::client_error::ClientError;
+use crate::client::network::dispatcher::Dispatcher;
+
+/// Subscribes the connection to the quotes of a list of symbols.
+///
+/// Once subscribed, the server pushes every new value of the symbols, received with
+/// ```recv_quote()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// symbols - The symbols to subscribe to.
+///
+/// Returns: every symbol the connection is subscribed to, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     let mut quotes = dispatcher.pushes();
+///     subscribe(&dispatcher, &["AAPL", "MSFT"]).await?;
+///     let (symbol, stock_val) = recv_quote(&mut quotes).await?;
+/// ```
+pub async fn subscribe(
+    dispatcher: &Dispatcher,
+    symbols: &[&str],
+) -> Result<Vec<String>, ClientError> {
+    let request = Request::Subscribe(symbols.iter().map(|symbol| symbol.to_string()).collect());
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::Subscribed(subscriptions)) => Ok(subscriptions),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientSubscribeFailed,
+        )),
+    }
+}
+
+/// Unsubscribes the connection from the quotes of a list of symbols.
+///
+/// Quotes already pushed by the server may still be received.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// dispatcher - The dispatcher of the connection to use.
+/// symbols - The symbols to unsubscribe from.
+///
+/// Returns: every symbol the connection is still subscribed to, ```ClientError``` on failure.
+///
+/// Example:
+/// ```rust
+///     unsubscribe(&dispatcher, &["MSFT"]).await?;
+/// ```
+pub async fn unsubscribe(
+    dispatcher: &Dispatcher,
+    symbols: &[&str],
+) -> Result<Vec<String>, ClientError> {
+    let request = Request::Unsubscribe(symbols.iter().map(|symbol| symbol.to_string()).collect());
+    let response: Message = dispatcher.request(request.to_message()).await?;
+
+    match Response::from_message(&response) {
+        Ok(Response::Unsubscribed(subscriptions)) => Ok(subscriptions),
+        response => Err(ClientError::from_response(
+            response,
+            ReturnFlags::ClientSubscribeFailed,
+        )),
+    }
+}
+
+/// Waits for the next quote pushed by the server.
+///
+/// Other pushed messages are skipped. Quotes dropped because the receiver fell behind are
+/// logged and skipped.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// pushes - A receiver returned by ```Dispatcher::pushes()```.
+///
+/// Returns: the symbol and its new value, ```ClientError``` once the connection is closed.
+///
+/// Example:
+/// ```rust
+///     while let Ok((symbol, stock_val)) = recv_quote(&mut quotes).await {
+///         println!("{}: {}", symbol, stock_val);
+///     }
+/// ```
+pub async fn recv_quote(
+    pushes: &mut broadcast::Receiver<Message>,
+) -> Result<(String, StockVal), ClientError> {
+    loop {
+        match pushes.recv().await {
+            Ok(message) => {
+                if let Ok(Response::Quote(symbol, stock_val)) = Response::from_message(&message) {
+                    return Ok((symbol, stock_val));
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("RECV_QUOTE_SKIPPED: {}", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                return Err(ClientError::Io(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "DISPATCHER_CONNECTION_CLOSED",
+                )))
+            }
+        }
+    }
+}
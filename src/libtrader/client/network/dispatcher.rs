@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::{interval, Instant};
+use tokio_util::codec::Framed;
+
+use crate::common::message::compression::decompress_message;
+use crate::common::message::inst::CommandInst;
+use crate::common::message::message::Message;
+use crate::common::message::message_codec::MessageCodec;
+use crate::common::message::message_parts::MessageAssembler;
+use crate::common::message::message_type::MessageType;
+use crate::common::message::request::Request;
+use crate::common::misc::return_flags::ReturnFlags;
+use crate::common::network::transport::Transport;
+
+/// Number of requests that may wait to be written before ```Dispatcher::request``` blocks.
+static DISPATCHER_QUEUE_LENGTH: usize = 64;
+
+/// Number of pushed messages kept for receivers that fall behind.
+static DISPATCHER_PUSH_CAPACITY: usize = 256;
+
+/// Longest time without sending or receiving anything before a ```Request::Ping``` is sent.
+pub static KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of keepalive intervals without receiving anything after which the server is
+/// considered dead.
+static KEEPALIVE_MAX_MISSED: u32 = 3;
+
+type PendingRequest = (Message, oneshot::Sender<io::Result<Message>>);
+
+/// Handle to a connection shared by concurrent requests.
+///
+/// The connection itself is owned by a background task, which tags every request with a fresh
+/// ```request_id``` and routes each response back to the request carrying the same id. Any
+/// number of requests may be in flight at once, and the server may answer them in any order.
+/// Responses sent in multiple parts are reassembled and decompressed before being returned.
+/// Messages pushed by the server, such as quotes, are forwarded to the receivers returned by
+/// ```Dispatcher::pushes```.
+/// The task keeps the connection alive with ```Request::Ping``` messages, and closes it if the
+/// server stops answering.
+/// The handle is cheap to clone, the task stops once every handle is dropped or the
+/// connection fails.
+///
+/// Example:
+/// ```rust
+///     let dispatcher = Dispatcher::new(socket);
+///     let (portfolio, transactions) = tokio::join!(
+///         acc_retrieve_portfolio(&dispatcher, jwt.clone()),
+///         acc_retrieve_transaction(&dispatcher, jwt),
+///     );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dispatcher {
+    requests: mpsc::Sender<PendingRequest>,
+    pushes: broadcast::Sender<Message>,
+}
+
+impl Dispatcher {
+    /// Spawns the dispatcher task owning ```socket```, using ```KEEPALIVE_INTERVAL```.
+    ///
+    /// The hello exchange must already be done on ```socket```.
+    pub fn new<T: Transport>(socket: Framed<T, MessageCodec>) -> Self {
+        Dispatcher::with_keepalive(socket, KEEPALIVE_INTERVAL)
+    }
+
+    /// Spawns the dispatcher task owning ```socket```, sending keepalives every
+    /// ```keepalive_interval``` of silence.
+    ///
+    /// The interval must be shorter than the idle timeout of the server.
+    pub fn with_keepalive<T: Transport>(
+        socket: Framed<T, MessageCodec>,
+        keepalive_interval: Duration,
+    ) -> Self {
+        let (requests, pending) = mpsc::channel(DISPATCHER_QUEUE_LENGTH);
+        let (pushes, _) = broadcast::channel(DISPATCHER_PUSH_CAPACITY);
+        tokio::spawn(dispatch(
+            socket,
+            pending,
+            pushes.clone(),
+            keepalive_interval,
+        ));
+        Dispatcher { requests, pushes }
+    }
+
+    /// Returns a receiver of the messages pushed by the server from now on.
+    ///
+    /// Receivers falling more than ```DISPATCHER_PUSH_CAPACITY``` messages behind skip the
+    /// oldest ones.
+    pub fn pushes(&self) -> broadcast::Receiver<Message> {
+        self.pushes.subscribe()
+    }
+
+    /// Sends a request and waits for its response.
+    ///
+    /// Arguments:
+    /// message - The request to send, its ```request_id``` is overwritten.
+    ///
+    /// Returns: ```io::Result``` wrapping the response, fails if the connection is closed
+    /// before the response arrives or if parts of the response are missing.
+    pub async fn request(&self, message: Message) -> io::Result<Message> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.requests
+            .send((message, response_tx))
+            .await
+            .map_err(|_| {
+                io::Error::new(io::ErrorKind::BrokenPipe, "DISPATCHER_CONNECTION_CLOSED")
+            })?;
+        response_rx.await.map_err(|_| {
+            io::Error::new(io::ErrorKind::UnexpectedEof, "DISPATCHER_CONNECTION_CLOSED")
+        })?
+    }
+}
+
+/// Body of the dispatcher task.
+///
+/// Writes queued requests and routes responses until the connection fails, the server stops
+/// answering keepalives or every ```Dispatcher``` is dropped. Requests still waiting are failed
+/// by dropping their sender.
+async fn dispatch<T: Transport>(
+    mut socket: Framed<T, MessageCodec>,
+    mut requests: mpsc::Receiver<PendingRequest>,
+    pushes: broadcast::Sender<Message>,
+    keepalive_interval: Duration,
+) {
+    let mut waiting: HashMap<u64, oneshot::Sender<io::Result<Message>>> = HashMap::new();
+    let mut partial: HashMap<u64, MessageAssembler> = HashMap::new();
+    let mut last_request_id: u64 = 0;
+
+    let mut keepalive = interval(keepalive_interval / 2);
+    let mut last_sent = Instant::now();
+    let mut last_received = Instant::now();
+
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some((mut message, response_tx)) => {
+                    /* request id 0 is reserved for messages answering no request */
+                    last_request_id = last_request_id.wrapping_add(1).max(1);
+                    message.request_id = last_request_id;
+                    waiting.insert(last_request_id, response_tx);
+                    if let Err(err) = socket.send(message).await {
+                        warn!("DISPATCHER_WRITE_FAILED: {}", err);
+                        break;
+                    }
+                    last_sent = Instant::now();
+                }
+                None => break,
+            },
+            _ = keepalive.tick() => {
+                if last_received.elapsed() > keepalive_interval * KEEPALIVE_MAX_MISSED {
+                    warn!("DISPATCHER_KEEPALIVE_FAILED: {}", ReturnFlags::ClientConnPeerTimeout);
+                    break;
+                }
+                if last_sent.elapsed() >= keepalive_interval
+                    || last_received.elapsed() >= keepalive_interval
+                {
+                    if let Err(err) = socket.send(Request::Ping.to_message()).await {
+                        warn!("DISPATCHER_WRITE_FAILED: {}", err);
+                        break;
+                    }
+                    last_sent = Instant::now();
+                }
+            }
+            response = socket.next() => match response {
+                Some(Ok(response)) => {
+                    last_received = Instant::now();
+                    if response.msgtype == MessageType::ServerReturn
+                        && response.instruction == CommandInst::Ping as i64
+                        && response.request_id == 0
+                    {
+                        /* answer to a keepalive, requested pings are routed as usual */
+                        continue;
+                    }
+
+                    let request_id = response.request_id;
+                    if request_id == 0 {
+                        /* nobody listening for pushes is not an error */
+                        match decompress_message(response) {
+                            Ok(push) => {
+                                let _ = pushes.send(push);
+                            }
+                            Err(flag) => warn!("DISPATCHER_PUSH_INVALID: {}", flag),
+                        }
+                        continue;
+                    }
+                    if !waiting.contains_key(&request_id) {
+                        warn!("DISPATCHER_UNKNOWN_REQUEST_ID: {}", request_id);
+                        continue;
+                    }
+
+                    /* wait for the remaining parts of the response */
+                    let assembler = partial.entry(request_id).or_default();
+                    let response = match assembler.push(response).and_then(|response| {
+                        response.map(decompress_message).transpose()
+                    }) {
+                        Ok(Some(response)) => Ok(response),
+                        Ok(None) => continue,
+                        Err(flag) => Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("{}", flag),
+                        )),
+                    };
+                    partial.remove(&request_id);
+                    if let Some(response_tx) = waiting.remove(&request_id) {
+                        /* the caller may have given up on the response */
+                        let _ = response_tx.send(response);
+                    }
+                }
+                Some(Err(err)) => {
+                    warn!("DISPATCHER_READ_FAILED: {}", err);
+                    break;
+                }
+                None => break,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::response::Response;
+
+    #[tokio::test]
+    async fn test_dispatcher_out_of_order_responses() {
+        let (client, server) = tokio::io::duplex(4096);
+        let dispatcher = Dispatcher::new(Framed::new(client, MessageCodec::new()));
+        let mut pushes = dispatcher.pushes();
+        let mut server = Framed::new(server, MessageCodec::new());
+
+        let first = dispatcher.request(Request::GetEmailSalt("a".to_string()).to_message());
+        let second = dispatcher.request(Request::GetPasswordSalt("b".to_string()).to_message());
+        let serve = async {
+            let first = server.next().await.unwrap().unwrap();
+            let second = server.next().await.unwrap().unwrap();
+
+            /* a keepalive answer and a push before the responses, in reverse order */
+            server.send(Response::Pong.to_message()).await.unwrap();
+            let mut push = Response::Registered.to_message();
+            push.request_id = 0;
+            server.send(push).await.unwrap();
+            for (request, salt) in [(second, vec![2u8]), (first, vec![1u8])] {
+                let mut response = Response::EmailSalt(salt).to_message();
+                response.request_id = request.request_id;
+                server.send(response).await.unwrap();
+            }
+        };
+
+        let (first, second, _) = tokio::join!(first, second, serve);
+        assert_eq!(
+            Response::from_message(&first.unwrap()),
+            Ok(Response::EmailSalt(vec![1u8]))
+        );
+        assert_eq!(
+            Response::from_message(&second.unwrap()),
+            Ok(Response::EmailSalt(vec![2u8]))
+        );
+        assert_eq!(
+            Response::from_message(&pushes.recv().await.unwrap()),
+            Ok(Response::Registered)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_requested_ping() {
+        let (client, server) = tokio::io::duplex(4096);
+        let dispatcher = Dispatcher::new(Framed::new(client, MessageCodec::new()));
+        let mut server = Framed::new(server, MessageCodec::new());
+
+        let ping = dispatcher.request(Request::Ping.to_message());
+        let serve = async {
+            let request = server.next().await.unwrap().unwrap();
+            assert_ne!(request.request_id, 0);
+
+            /* the keepalive answer is swallowed, the requested one is returned */
+            server.send(Response::Pong.to_message()).await.unwrap();
+            let mut response = Response::Pong.to_message();
+            response.request_id = request.request_id;
+            server.send(response).await.unwrap();
+        };
+
+        let (ping, _) = tokio::join!(ping, serve);
+        assert_eq!(Response::from_message(&ping.unwrap()), Ok(Response::Pong));
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_connection_closed() {
+        let (client, server) = tokio::io::duplex(4096);
+        let dispatcher = Dispatcher::new(Framed::new(client, MessageCodec::new()));
+        drop(server);
+
+        assert!(dispatcher
+            .request(Request::GenHashSalt.to_message())
+            .await
+            .is_err());
+    }
+}
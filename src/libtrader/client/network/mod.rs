@@ -1,3 +1,5 @@
+pub mod client_error;
 pub mod cmd;
+pub mod dispatcher;
 pub mod gen_tls_client_config;
 pub mod handle_data;
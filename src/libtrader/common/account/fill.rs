@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The execution of an order.
+///
+/// Members:
+/// stock_symbol - The symbol of the stock traded.
+/// quantity - The number of shares traded.
+/// price - The price of one share.
+/// time_epoch - The unix epoch at which the order was executed.
+/// realized_pnl - The profit, or loss if negative, made on the shares sold. ```0``` for
+/// purchases.
+/// order_id - The id of the ```Order``` filled, ```0``` for market purchases and sales.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default, Clone)]
+pub struct Fill {
+    pub stock_symbol: String,
+    pub quantity: i64,
+    pub price: f64,
+    pub time_epoch: i64,
+    pub realized_pnl: f64,
+    pub order_id: i64,
+}
+impl std::fmt::Display for Fill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({}, {}, {}, {}, {}, {})",
+            self.stock_symbol,
+            self.quantity,
+            self.price,
+            self.time_epoch,
+            self.realized_pnl,
+            self.order_id
+        )
+    }
+}
@@ -1,5 +1,7 @@
+pub mod fill;
 pub mod hash;
 pub mod order;
+pub mod order_group;
 pub mod portfolio;
 pub mod position;
 pub mod session;
@@ -1,24 +1,285 @@
-#[derive(PartialEq, Debug)]
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Lifecycle state of an ```Order```.
+///
+/// Variants:
+/// Open - The order rests on the server, waiting to be filled.
+/// Pending - The order waits for another order of its group, e.g. the exits of a bracket
+/// wait for the entry to fill.
+/// Filled - Every share of the order was filled.
+/// Rejected - The order could not be filled, e.g. for lack of cash or shares.
+/// Cancelled - The order was cancelled before every share was filled.
+/// Expired - The time in force of the order ran out before every share was filled.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum OrderStatus {
+    #[default]
+    Open,
+    Pending,
+    Filled,
+    Rejected,
+    Cancelled,
+    Expired,
+}
+
+impl OrderStatus {
+    /// Returns the name of the status as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Open => "open",
+            OrderStatus::Pending => "pending",
+            OrderStatus::Filled => "filled",
+            OrderStatus::Rejected => "rejected",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Expired => "expired",
+        }
+    }
+
+    /// Parses a status stored in the database.
+    pub fn from_db(status: &str) -> Option<Self> {
+        match status {
+            "open" => Some(OrderStatus::Open),
+            "pending" => Some(OrderStatus::Pending),
+            "filled" => Some(OrderStatus::Filled),
+            "rejected" => Some(OrderStatus::Rejected),
+            "cancelled" => Some(OrderStatus::Cancelled),
+            "expired" => Some(OrderStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the order can no longer change.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled
+                | OrderStatus::Rejected
+                | OrderStatus::Cancelled
+                | OrderStatus::Expired
+        )
+    }
+}
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Kind of an ```Order```, deciding when and at which price it is filled.
+///
+/// Stop orders stay dormant until the market crosses their ```stop_price```, they are then
+/// triggered and filled like market or limit orders. Sell stops trigger when the bid falls to
+/// the stop, buy stops when the ask rises to it.
+///
+/// Variants:
+/// Limit - Filled at the limit price or better.
+/// Stop - Filled at the market price once triggered.
+/// StopLimit - Filled at the limit price or better once triggered.
+/// TrailingStop - A stop following the market at ```trail_amount```, filled at the market
+/// price once triggered.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum OrderType {
+    #[default]
+    Limit,
+    Stop,
+    StopLimit,
+    TrailingStop,
+}
+
+impl OrderType {
+    /// Returns the name of the type as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Limit => "limit",
+            OrderType::Stop => "stop",
+            OrderType::StopLimit => "stop_limit",
+            OrderType::TrailingStop => "trailing_stop",
+        }
+    }
+
+    /// Parses a type stored in the database.
+    pub fn from_db(order_type: &str) -> Option<Self> {
+        match order_type {
+            "limit" => Some(OrderType::Limit),
+            "stop" => Some(OrderType::Stop),
+            "stop_limit" => Some(OrderType::StopLimit),
+            "trailing_stop" => Some(OrderType::TrailingStop),
+            _ => None,
+        }
+    }
+
+    /// Returns whether orders of the type wait for a trigger before being filled.
+    pub fn is_stop(&self) -> bool {
+        *self != OrderType::Limit
+    }
+
+    /// Returns whether orders of the type are bounded by a limit price.
+    pub fn has_limit(&self) -> bool {
+        matches!(self, OrderType::Limit | OrderType::StopLimit)
+    }
+}
+impl std::fmt::Display for OrderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// How long an ```Order``` stays open.
+///
+/// Variants:
+/// Gtc - Good till cancelled, the order stays open until it is filled or cancelled.
+/// Day - The order expires at the end of the exchange session it was placed in.
+/// Ioc - Immediate or cancel, what the latest quote cannot fill is cancelled.
+/// Fok - Fill or kill, the order is rejected unless the latest quote fills it completely.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum TimeInForce {
+    #[default]
+    Gtc,
+    Day,
+    Ioc,
+    Fok,
+}
+
+impl TimeInForce {
+    /// Returns the name of the time in force as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeInForce::Gtc => "gtc",
+            TimeInForce::Day => "day",
+            TimeInForce::Ioc => "ioc",
+            TimeInForce::Fok => "fok",
+        }
+    }
+
+    /// Parses a time in force stored in the database.
+    pub fn from_db(time_in_force: &str) -> Option<Self> {
+        match time_in_force {
+            "gtc" => Some(TimeInForce::Gtc),
+            "day" => Some(TimeInForce::Day),
+            "ioc" => Some(TimeInForce::Ioc),
+            "fok" => Some(TimeInForce::Fok),
+            _ => None,
+        }
+    }
+
+    /// Returns whether orders with the time in force never rest on the server.
+    pub fn is_immediate(&self) -> bool {
+        matches!(self, TimeInForce::Ioc | TimeInForce::Fok)
+    }
+}
+impl std::fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Part an ```Order``` plays in its ```OrderGroup```.
+///
+/// Variants:
+/// Single - The order belongs to no group.
+/// Entry - The entry of a bracket, whose fills activate the other orders of the group.
+/// Leg - An order cancelling the other legs of its group when it fills.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum GroupRole {
+    #[default]
+    Single,
+    Entry,
+    Leg,
+}
+
+impl GroupRole {
+    /// Returns the name of the role as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupRole::Single => "single",
+            GroupRole::Entry => "entry",
+            GroupRole::Leg => "leg",
+        }
+    }
+
+    /// Parses a role stored in the database.
+    pub fn from_db(group_role: &str) -> Option<Self> {
+        match group_role {
+            "single" => Some(GroupRole::Single),
+            "entry" => Some(GroupRole::Entry),
+            "leg" => Some(GroupRole::Leg),
+            _ => None,
+        }
+    }
+}
+impl std::fmt::Display for GroupRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// An order, as stored in ```accounts_schema.orders```.
+///
+/// Members:
+/// id - The id of the order, ```0``` until it is stored.
+/// is_buy - Whether the order buys or sells shares.
+/// stock_symbol - The symbol of the stock.
+/// order_type - The kind of the order.
+/// stock_price - The limit price, the worst price of one share the order accepts. Unused by
+/// orders filled at the market price.
+/// stock_amount - The number of shares of the order.
+/// stock_filled - The number of shares filled so far.
+/// is_filled - Whether every share of the order was filled.
+/// status - The lifecycle state of the order.
+/// created_epoch - The unix epoch at which the order was placed.
+/// stop_price - The price triggering a stop order. Trailing stops move it as the market moves.
+/// trail_amount - The distance of a trailing stop to its ```water_mark```.
+/// water_mark - The best price seen by a trailing stop since it was placed, the highest bid
+/// for sells and the lowest ask for buys.
+/// is_triggered - Whether a stop order was triggered. Unused by limit orders.
+/// group_id - The id of the ```OrderGroup``` of the order, ```0``` if it has none.
+/// group_role - The part the order plays in its group.
+/// time_in_force - How long the order stays open.
+/// expire_epoch - The unix epoch at which the order expires, ```0``` if it never does.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default, Clone)]
 pub struct Order {
+    pub id: i64,
     pub is_buy: bool,
     pub stock_symbol: String,
+    pub order_type: OrderType,
     pub stock_price: f64,
-    pub stock_amount: isize,
-    pub stock_filled: isize,
+    pub stock_amount: i64,
+    pub stock_filled: i64,
     pub is_filled: bool,
+    pub status: OrderStatus,
+    pub created_epoch: i64,
+    pub stop_price: f64,
+    pub trail_amount: f64,
+    pub water_mark: f64,
+    pub is_triggered: bool,
+    pub group_id: i64,
+    pub group_role: GroupRole,
+    pub time_in_force: TimeInForce,
+    pub expire_epoch: i64,
 }
 impl std::fmt::Display for Order {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {}, {}, {}, {}, {})",
+            "({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {})",
+            self.id,
             self.is_buy,
             self.stock_symbol,
+            self.order_type,
             self.stock_price,
             self.stock_amount,
-            self.stock_amount,
             self.stock_filled,
-            self.is_filled
+            self.is_filled,
+            self.status,
+            self.created_epoch,
+            self.stop_price,
+            self.trail_amount,
+            self.water_mark,
+            self.is_triggered,
+            self.group_id,
+            self.group_role,
+            self.time_in_force,
+            self.expire_epoch
         )
     }
 }
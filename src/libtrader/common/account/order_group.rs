@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::common::account::order::Order;
+
+/// Kind of an ```OrderGroup```.
+///
+/// Variants:
+/// Oco - Two legs, one cancels the other: the first leg to fill cancels the other.
+/// Bracket - An entry followed by a take-profit and a stop-loss exit. The exits wait for the
+/// entry to fill, then cancel each other.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum GroupType {
+    #[default]
+    Oco,
+    Bracket,
+}
+
+impl GroupType {
+    /// Returns the name of the type as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupType::Oco => "oco",
+            GroupType::Bracket => "bracket",
+        }
+    }
+
+    /// Parses a type stored in the database.
+    pub fn from_db(group_type: &str) -> Option<Self> {
+        match group_type {
+            "oco" => Some(GroupType::Oco),
+            "bracket" => Some(GroupType::Bracket),
+            _ => None,
+        }
+    }
+}
+impl std::fmt::Display for GroupType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Orders linked together, as stored in ```accounts_schema.order_groups```.
+///
+/// Members:
+/// id - The id of the group.
+/// group_type - The kind of the group.
+/// orders - The orders of the group, the entry of a bracket first.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default, Clone)]
+pub struct OrderGroup {
+    pub id: i64,
+    pub group_type: GroupType,
+    pub orders: Vec<Order>,
+}
+impl std::fmt::Display for OrderGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, [", self.id, self.group_type)?;
+        for (i, order) in self.orders.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", order)?;
+        }
+        write!(f, "])")
+    }
+}
@@ -1,14 +1,21 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::common::account::position::Position;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+/// The holdings of an account.
+///
+/// Members:
+/// open_positions - The positions of the account.
+/// cash_balance - The cash available to the account, in cents.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default)]
 pub struct Portfolio {
     pub open_positions: Vec<Position>,
+    pub cash_balance: i64,
 }
 
 impl std::fmt::Display for Portfolio {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({:#?})", self.open_positions)
+        write!(f, "({:#?}, {})", self.open_positions, self.cash_balance)
     }
 }
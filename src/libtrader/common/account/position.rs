@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default, Clone)]
 pub struct Position {
     pub is_buy: bool,
     pub stock_symbol: String,
@@ -1,18 +1,72 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+/// Line item of the transaction history.
+///
+/// Variants:
+/// Trade - The shares bought or sold.
+/// FlatFee - The fixed fee charged per trade.
+/// ShareFee - The fee charged per share traded.
+/// Commission - The commission charged as a percentage of the value traded.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum TransactionKind {
+    #[default]
+    Trade,
+    FlatFee,
+    ShareFee,
+    Commission,
+}
+
+impl TransactionKind {
+    /// Returns the name of the kind as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Trade => "trade",
+            TransactionKind::FlatFee => "flat_fee",
+            TransactionKind::ShareFee => "share_fee",
+            TransactionKind::Commission => "commission",
+        }
+    }
+
+    /// Parses a kind stored in the database.
+    pub fn from_db(kind: &str) -> Option<Self> {
+        match kind {
+            "trade" => Some(TransactionKind::Trade),
+            "flat_fee" => Some(TransactionKind::FlatFee),
+            "share_fee" => Some(TransactionKind::ShareFee),
+            "commission" => Some(TransactionKind::Commission),
+            _ => None,
+        }
+    }
+}
+
+/// A trade of an account, or a fee charged on it, as stored in
+/// ```accounts_schema.transactions```.
+///
+/// Members:
+/// stock_symbol - The symbol of the stock traded.
+/// shares_size - The number of shares traded.
+/// shares_cost - The total cost of the shares, or the amount of the fee, in cents.
+/// is_buy - Whether the shares were bought or sold.
+/// kind - Whether the line is the trade itself or one of its fees.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default)]
 pub struct Transaction {
     pub stock_symbol: String,
     pub shares_size: i64,
     pub shares_cost: i64,
     pub is_buy: bool,
+    pub kind: TransactionKind,
 }
 impl std::fmt::Display for Transaction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {}, {})",
-            self.stock_symbol, self.shares_size, self.shares_cost, self.is_buy
+            "({}, {}, {}, {}, {})",
+            self.stock_symbol,
+            self.shares_size,
+            self.shares_cost,
+            self.is_buy,
+            self.kind.as_str()
         )
     }
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Default, Eq, PartialEq, Clone, Debug)]
+#[derive(Serialize, Deserialize, ToSchema, Default, Eq, PartialEq, Clone, Debug)]
 pub struct Company {
     pub id: i64,
     pub symbol: String,
@@ -1,7 +1,8 @@
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Default, PartialEq, Debug, ToSql, FromSql, Serialize, Deserialize)]
+#[derive(Default, PartialEq, Debug, Clone, ToSql, FromSql, Serialize, Deserialize, ToSchema)]
 pub struct StockVal {
     pub id: i64,
     pub isin: String,
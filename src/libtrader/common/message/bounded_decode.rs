@@ -0,0 +1,50 @@
+use bincode::Options;
+use serde::de::DeserializeOwned;
+
+/// Decodes a bincode encoded value out of untrusted bytes.
+///
+/// Uses the same encoding as ```bincode::serialize```, but never reads more than ```bytes```
+/// holds: a forged length prefix fails right away instead of allocating for the claimed size.
+/// Trailing bytes are rejected.
+///
+/// Arguments:
+/// bytes - The encoded value.
+///
+/// Returns: the decoded value, ```bincode::Error``` if ```bytes``` does not hold exactly one
+/// valid value.
+///
+/// Example:
+/// ```rust
+///     let request: Request = bounded_deserialize(&message.data)?;
+/// ```
+pub fn bounded_deserialize<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(bytes.len() as u64)
+        .deserialize(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bounded_deserialize() {
+        let encoded = bincode::serialize(&("AAPL".to_string(), vec![1u8, 2, 3])).unwrap();
+        assert_eq!(
+            bounded_deserialize::<(String, Vec<u8>)>(&encoded).unwrap(),
+            ("AAPL".to_string(), vec![1u8, 2, 3])
+        );
+
+        /* truncated and trailing bytes */
+        assert!(bounded_deserialize::<(String, Vec<u8>)>(&encoded[..encoded.len() - 1]).is_err());
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert!(bounded_deserialize::<(String, Vec<u8>)>(&trailing).is_err());
+
+        /* a length prefix claiming far more than the input */
+        let forged = u64::MAX.to_le_bytes();
+        assert!(bounded_deserialize::<Vec<u8>>(&forged).is_err());
+        assert!(bounded_deserialize::<String>(&forged).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+use crate::common::message::hello::Capability;
+use crate::common::message::message::Message;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Data shorter than this is never compressed, in bytes.
+pub static COMPRESSION_THRESHOLD: usize = 1024;
+
+/// The largest data a compressed message may expand to, in bytes.
+pub static MAX_DECOMPRESSED_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Compression applied to the ```data``` of a message.
+///
+/// The compression of a connection is negotiated during the hello exchange, see
+/// ```Compression::negotiate```. Only responses are compressed.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum Compression {
+    #[default]
+    None = 0,
+    Deflate = 1,
+    Zstd = 2,
+}
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+
+impl std::convert::TryFrom<u8> for Compression {
+    type Error = ReturnFlags;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Deflate),
+            2 => Ok(Compression::Zstd),
+            _ => Err(ReturnFlags::CommonMsgDecodeFailed),
+        }
+    }
+}
+
+impl Compression {
+    /// Picks the compression of a connection, zstd being preferred over deflate.
+    ///
+    /// Arguments:
+    /// shared_capabilities - The capabilities supported by both peers, see
+    /// ```Hello::shared_capabilities```.
+    pub fn negotiate(shared_capabilities: u32) -> Compression {
+        if shared_capabilities & Capability::CompressionZstd as u32 != 0 {
+            Compression::Zstd
+        } else if shared_capabilities & Capability::Compression as u32 != 0 {
+            Compression::Deflate
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Compresses ```data```.
+    pub fn compress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            Compression::Deflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Compression::Zstd => zstd::encode_all(data, 0),
+        }
+    }
+
+    /// Decompresses ```data```, refusing to expand it past ```MAX_DECOMPRESSED_LENGTH```.
+    ///
+    /// Returns: the decompressed data, ```ReturnFlags::CommonMsgDecompressFailed``` on error.
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>, ReturnFlags> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Compression::None => return Ok(data.to_vec()),
+            Compression::Deflate => Box::new(flate2::read::DeflateDecoder::new(data)),
+            Compression::Zstd => Box::new(
+                zstd::stream::read::Decoder::new(data)
+                    .map_err(|_| ReturnFlags::CommonMsgDecompressFailed)?,
+            ),
+        };
+
+        /* read one byte past the limit to tell a full buffer from an oversized one */
+        let mut decompressed = Vec::new();
+        decoder
+            .take(MAX_DECOMPRESSED_LENGTH as u64 + 1)
+            .read_to_end(&mut decompressed)
+            .map_err(|_| ReturnFlags::CommonMsgDecompressFailed)?;
+        if decompressed.len() > MAX_DECOMPRESSED_LENGTH {
+            return Err(ReturnFlags::CommonMsgDecompressFailed);
+        }
+        Ok(decompressed)
+    }
+}
+
+/// Compresses the data of a message about to be sent.
+///
+/// Data shorter than ```COMPRESSION_THRESHOLD```, or not getting any smaller, is left as is.
+/// Compression must happen before the message is split.
+///
+/// Arguments:
+/// message - The message to compress, not compressed yet.
+/// compression - The compression negotiated for the connection.
+///
+/// Returns: the message, with ```compression``` set to the compression applied.
+///
+/// Example:
+/// ```rust
+///     let message = compress_message(response.to_message(), compression);
+/// ```
+pub fn compress_message(mut message: Message, compression: Compression) -> Message {
+    if compression == Compression::None || message.data.len() < COMPRESSION_THRESHOLD {
+        return message;
+    }
+    if let Ok(data) = compression.compress(&message.data) {
+        if data.len() < message.data.len() {
+            message.data = data;
+            message.compression = compression;
+        }
+    }
+    message
+}
+
+/// Decompresses the data of a received message, once all of its parts are assembled.
+///
+/// Arguments:
+/// message - The received message.
+///
+/// Returns: the message with ```compression``` reset, ```ReturnFlags::CommonMsgDecompressFailed```
+/// on error.
+pub fn decompress_message(mut message: Message) -> Result<Message, ReturnFlags> {
+    message.data = message.compression.decompress(&message.data)?;
+    message.compression = Compression::None;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::message_builder::message_builder;
+    use crate::common::message::message_type::MessageType;
+
+    #[test]
+    fn test_compression_roundtrip() {
+        let data: Vec<u8> = (0..8192).map(|x| (x % 7) as u8).collect();
+        for compression in [Compression::Deflate, Compression::Zstd] {
+            let message = message_builder(MessageType::ServerReturn, 11, 1, 0, 0, data.clone());
+            let compressed = compress_message(message.clone(), compression);
+            assert_eq!(compressed.compression, compression);
+            assert!(compressed.data.len() < data.len());
+            assert_eq!(decompress_message(compressed), Ok(message));
+        }
+
+        /* short data is sent as is */
+        let message = message_builder(MessageType::ServerReturn, 11, 1, 0, 0, vec![1u8; 16]);
+        assert_eq!(
+            compress_message(message.clone(), Compression::Zstd),
+            message
+        );
+    }
+
+    #[test]
+    fn test_compression_negotiate() {
+        let both = Capability::Compression as u32 | Capability::CompressionZstd as u32;
+        assert_eq!(Compression::negotiate(both), Compression::Zstd);
+        assert_eq!(
+            Compression::negotiate(Capability::Compression as u32),
+            Compression::Deflate
+        );
+        assert_eq!(
+            Compression::negotiate(Capability::Subscriptions as u32),
+            Compression::None
+        );
+    }
+
+    #[test]
+    fn test_decompress_invalid() {
+        assert_eq!(
+            Compression::Zstd.decompress(&[1, 2, 3]),
+            Err(ReturnFlags::CommonMsgDecompressFailed)
+        );
+
+        /* data expanding past the limit */
+        let bomb = Compression::Zstd
+            .compress(&vec![0u8; MAX_DECOMPRESSED_LENGTH + 1])
+            .unwrap();
+        assert_eq!(
+            Compression::Zstd.decompress(&bomb),
+            Err(ReturnFlags::CommonMsgDecompressFailed)
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+/// Version of the wire protocol spoken by this library.
+///
+/// Bumped on every change that makes older peers unable to talk to newer ones.
+pub static PROTOCOL_VERSION: u32 = 1;
+
+/// Optional protocol features, each one bit of ```Hello::capabilities```.
+///
+/// ```Compression``` stands for deflate compression, ```CompressionZstd``` for zstd.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Capability {
+    Compression = 1,
+    Subscriptions = 2,
+    CompressionZstd = 4,
+}
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+
+/// Optional features implemented by this library.
+pub static SUPPORTED_CAPABILITIES: u32 = Capability::Compression as u32
+    | Capability::Subscriptions as u32
+    | Capability::CompressionZstd as u32;
+
+/// Hello exchanged by both peers at connection start.
+///
+/// The client sends its hello first, the server answers with its own hello or refuses the
+/// connection. No other request is accepted before the exchange.
+///
+/// Members:
+/// protocol_version - The ```PROTOCOL_VERSION``` of the peer.
+/// library_version - The Cargo version of the peer library.
+/// capabilities - Bitmask of ```Capability``` supported by the peer.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Hello {
+    pub protocol_version: u32,
+    pub library_version: String,
+    pub capabilities: u32,
+}
+
+impl Hello {
+    /// Creates the hello describing this library.
+    pub fn new() -> Self {
+        Hello {
+            protocol_version: PROTOCOL_VERSION,
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities: SUPPORTED_CAPABILITIES,
+        }
+    }
+
+    /// Returns whether a peer sending ```peer``` can be talked to.
+    pub fn is_compatible(&self, peer: &Hello) -> bool {
+        self.protocol_version == peer.protocol_version
+    }
+
+    /// Returns the capabilities supported by both peers.
+    pub fn shared_capabilities(&self, peer: &Hello) -> u32 {
+        self.capabilities & peer.capabilities
+    }
+
+    /// Returns whether ```capability``` is advertised in the hello.
+    pub fn has_capability(&self, capability: Capability) -> bool {
+        self.capabilities & capability as u32 != 0
+    }
+}
+
+impl Default for Hello {
+    fn default() -> Self {
+        Hello::new()
+    }
+}
+
+impl std::fmt::Display for Hello {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "(protocol {}, libtrader {}, capabilities {:#b})",
+            self.protocol_version, self.library_version, self.capabilities
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hello_compatibility() {
+        let local = Hello::new();
+        assert!(local.is_compatible(&Hello::new()));
+
+        /* a different library version is fine as long as the protocol matches */
+        let mut peer = Hello::new();
+        peer.library_version = "0.0.1".to_string();
+        assert!(local.is_compatible(&peer));
+
+        peer.protocol_version = PROTOCOL_VERSION + 1;
+        assert!(!local.is_compatible(&peer));
+    }
+
+    #[test]
+    fn test_hello_shared_capabilities() {
+        let mut local = Hello::new();
+        local.capabilities = Capability::Compression as u32 | Capability::Subscriptions as u32;
+        let mut peer = Hello::new();
+        peer.capabilities = Capability::Subscriptions as u32;
+
+        let shared = local.shared_capabilities(&peer);
+        assert_eq!(shared, Capability::Subscriptions as u32);
+        assert!(peer.has_capability(Capability::Subscriptions));
+        assert!(!peer.has_capability(Capability::Compression));
+    }
+}
@@ -11,6 +11,14 @@ pub enum CommandInst {
     GenHashSalt = 6,
     GetEmailSalt = 7,
     GetPasswordSalt = 8,
+    Hello = 9,
+    Subscribe = 10,
+    Unsubscribe = 11,
+    Ping = 12,
+    PlaceOrder = 13,
+    PlaceOrderGroup = 14,
+    CancelOrder = 15,
+    AmendOrder = 16,
 }
 impl std::fmt::Display for CommandInst {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -19,7 +27,7 @@ impl std::fmt::Display for CommandInst {
 }
 
 #[allow(dead_code)]
-static INST_COMMAND_MAX_ID: isize = CommandInst::GetPasswordSalt as isize;
+static INST_COMMAND_MAX_ID: isize = CommandInst::AmendOrder as isize;
 
 #[derive(PartialEq, Debug)]
 pub enum DataTransferInst {
@@ -29,6 +37,9 @@ pub enum DataTransferInst {
     GetUserInfo = 9,
     GetUserPortfolio = 10,
     GetUserTransactionHist = 11,
+    PushQuote = 12,
+    GetUserOpenOrders = 13,
+    GetUserOrderHistory = 14,
 }
 impl std::fmt::Display for DataTransferInst {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -37,4 +48,4 @@ impl std::fmt::Display for DataTransferInst {
 }
 
 #[allow(dead_code)]
-static INST_DATA_MAX_ID: isize = DataTransferInst::GetUserTransactionHist as isize;
+static INST_DATA_MAX_ID: isize = DataTransferInst::GetUserOrderHistory as isize;
@@ -1,26 +1,33 @@
 use serde::{Deserialize, Serialize};
 
+use crate::common::message::compression::Compression;
 use crate::common::message::message_type::MessageType;
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
 pub struct Message {
     pub msgtype: MessageType,
+    /// Matches a response to its request, ```0``` if the message answers no request.
+    pub request_id: u64,
     pub instruction: i64,
     pub argument_count: usize,
     pub data_message_number: usize,
     pub data_message_max: usize,
+    /// Compression applied to ```data```.
+    pub compression: Compression,
     pub data: Vec<u8>,
 }
 impl std::fmt::Display for Message {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {}, {}, {}, {:#?})",
+            "({}, {}, {}, {}, {}, {}, {}, {:#?})",
             self.msgtype,
+            self.request_id,
             self.instruction,
             self.argument_count,
             self.data_message_number,
             self.data_message_max,
+            self.compression,
             self.data
         )
     }
@@ -0,0 +1,205 @@
+use bytes::BytesMut;
+use std::io;
+
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::common::message::message::Message;
+use crate::common::message::message_header::{MessageHeader, HEADER_LENGTH};
+
+/// The largest message data accepted or produced by ```MessageCodec```, in bytes.
+pub static MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// Framing codec for ```Message```.
+///
+/// Every frame on the wire is a big-endian ```MessageHeader``` followed by the ```dataSize```
+/// bytes of data, as laid out in the protocol specification. Frames are reassembled across
+/// reads, and a single read holding multiple frames is split into separate messages. Frames
+/// announcing more data than the configured maximum are rejected before being buffered.
+///
+/// Example:
+/// ```rust
+///     let mut socket = Framed::new(tls_stream, MessageCodec::new());
+///     socket.send(message).await?;
+///     let response = socket.next().await;
+/// ```
+#[derive(Debug)]
+pub struct MessageCodec {
+    max_frame_length: usize,
+}
+
+impl MessageCodec {
+    /// Creates a codec using ```MAX_FRAME_LENGTH```.
+    pub fn new() -> Self {
+        MessageCodec::with_max_frame_length(MAX_FRAME_LENGTH)
+    }
+
+    /// Creates a codec that rejects messages carrying more than ```max_frame_length``` bytes of
+    /// data.
+    pub fn with_max_frame_length(max_frame_length: usize) -> Self {
+        MessageCodec { max_frame_length }
+    }
+}
+
+impl Default for MessageCodec {
+    fn default() -> Self {
+        MessageCodec::new()
+    }
+}
+
+impl Decoder for MessageCodec {
+    type Item = Message;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Message>> {
+        if src.len() < HEADER_LENGTH {
+            return Ok(None);
+        }
+        let header = MessageHeader::decode(&src[..HEADER_LENGTH]).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("MESSAGE_CODEC_DECODE_FAILED: {}", err),
+            )
+        })?;
+
+        let data_size = header.data_size as usize;
+        if data_size > self.max_frame_length {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("MESSAGE_CODEC_FRAME_TOO_LARGE: {}", data_size),
+            ));
+        }
+        if src.len() < HEADER_LENGTH + data_size {
+            src.reserve(HEADER_LENGTH + data_size - src.len());
+            return Ok(None);
+        }
+
+        let mut frame = src.split_to(HEADER_LENGTH + data_size);
+        let data = frame.split_off(HEADER_LENGTH);
+        Ok(Some(header.into_message(data.to_vec())))
+    }
+}
+
+impl Encoder<Message> for MessageCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> io::Result<()> {
+        if item.data.len() > self.max_frame_length {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("MESSAGE_CODEC_FRAME_TOO_LARGE: {}", item.data.len()),
+            ));
+        }
+        let header = MessageHeader::from_message(&item).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("MESSAGE_CODEC_ENCODE_FAILED: {}", err),
+            )
+        })?;
+
+        dst.reserve(HEADER_LENGTH + item.data.len());
+        header.encode(dst);
+        dst.extend_from_slice(&item.data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::message_builder::message_builder;
+    use crate::common::message::message_type::MessageType;
+
+    fn sample_message(data: Vec<u8>) -> Message {
+        message_builder(MessageType::DataTransfer, 10, 1, 0, 1, data)
+    }
+
+    #[test]
+    fn test_message_codec_split_frame() {
+        let mut codec = MessageCodec::new();
+        let mut encoded = BytesMut::new();
+        codec
+            .encode(sample_message(vec![7u8; 6000]), &mut encoded)
+            .unwrap();
+
+        /* feed the frame in two reads */
+        let mut src = BytesMut::new();
+        src.extend_from_slice(&encoded[..100]);
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+        src.extend_from_slice(&encoded[100..]);
+        assert_eq!(
+            codec.decode(&mut src).unwrap(),
+            Some(sample_message(vec![7u8; 6000]))
+        );
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_message_codec_coalesced_frames() {
+        let mut codec = MessageCodec::new();
+        let mut src = BytesMut::new();
+        codec.encode(sample_message(vec![1, 2]), &mut src).unwrap();
+        codec.encode(sample_message(vec![3]), &mut src).unwrap();
+
+        assert_eq!(
+            codec.decode(&mut src).unwrap(),
+            Some(sample_message(vec![1, 2]))
+        );
+        assert_eq!(
+            codec.decode(&mut src).unwrap(),
+            Some(sample_message(vec![3]))
+        );
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    fn test_message_codec_max_frame_length() {
+        let mut codec = MessageCodec::with_max_frame_length(64);
+        let mut src = BytesMut::new();
+        assert!(codec
+            .encode(sample_message(vec![0u8; 128]), &mut src)
+            .is_err());
+
+        /* a forged dataSize must be rejected without waiting for the data */
+        let mut src = BytesMut::new();
+        codec
+            .encode(sample_message(vec![0u8; 8]), &mut src)
+            .unwrap();
+        src[3..7].copy_from_slice(&1024u32.to_be_bytes());
+        assert!(codec.decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn test_message_codec_golden_bytes() {
+        let mut codec = MessageCodec::new();
+        let mut message = sample_message(vec![0xde, 0xad]);
+        message.request_id = 7;
+
+        let mut encoded = BytesMut::new();
+        codec.encode(message.clone(), &mut encoded).unwrap();
+        assert_eq!(
+            &encoded[..],
+            &[
+                0x01, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0xde, 0xad,
+            ][..]
+        );
+        assert_eq!(codec.decode(&mut encoded).unwrap(), Some(message));
+    }
+
+    #[test]
+    fn test_message_codec_malformed_frame() {
+        let mut codec = MessageCodec::new();
+
+        /* unknown message type */
+        let mut src = BytesMut::new();
+        codec.encode(sample_message(vec![1, 2]), &mut src).unwrap();
+        src[0] = 0xff;
+        assert!(codec.decode(&mut src).is_err());
+
+        /* the largest dataSize there is */
+        let mut src = BytesMut::new();
+        codec.encode(sample_message(vec![1, 2]), &mut src).unwrap();
+        src[3..7].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(codec.decode(&mut src).is_err());
+    }
+}
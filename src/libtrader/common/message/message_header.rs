@@ -0,0 +1,190 @@
+use bytes::{Buf, BufMut, BytesMut};
+use std::convert::TryFrom;
+
+use crate::common::message::compression::Compression;
+use crate::common::message::message::Message;
+use crate::common::message::message_type::MessageType;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Length of an encoded ```MessageHeader```, in bytes.
+pub static HEADER_LENGTH: usize = 26;
+
+/// Header sent in front of the data of every ```Message```.
+///
+/// All fields are big-endian, in the order of the protocol specification, followed by the
+/// ```request_id``` and ```compression``` this library adds:
+///
+/// | offset | size | field               |
+/// |--------|------|---------------------|
+/// | 0      | 1    | messageType         |
+/// | 1      | 2    | instruction         |
+/// | 3      | 4    | dataSize            |
+/// | 7      | 2    | argumentCount       |
+/// | 9      | 4    | dataMessageNumber   |
+/// | 13     | 4    | dataMessageMax      |
+/// | 17     | 8    | requestId           |
+/// | 25     | 1    | compression         |
+/// | 26     | n    | data (dataSize)     |
+///
+/// Members:
+/// msgtype - The ```MessageType``` of the message.
+/// instruction - The instruction of the message.
+/// data_size - The length of the data following the header.
+/// argument_count - The number of arguments carried in the data.
+/// data_message_number - The number of this part, see ```split_message()```.
+/// data_message_max - The number of parts, ```0``` for messages sent whole.
+/// request_id - The ```request_id``` of the message.
+/// compression - The ```Compression``` applied to the data.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct MessageHeader {
+    pub msgtype: MessageType,
+    pub instruction: u16,
+    pub data_size: u32,
+    pub argument_count: u16,
+    pub data_message_number: u32,
+    pub data_message_max: u32,
+    pub request_id: u64,
+    pub compression: Compression,
+}
+
+impl MessageHeader {
+    /// Builds the header of a message.
+    ///
+    /// Arguments:
+    /// message - The message to describe.
+    ///
+    /// Returns: the header, ```ReturnFlags::CommonMsgEncodeFailed``` if a field of the message
+    /// does not fit its wire size.
+    pub fn from_message(message: &Message) -> Result<MessageHeader, ReturnFlags> {
+        let encode_failed = |_| ReturnFlags::CommonMsgEncodeFailed;
+        Ok(MessageHeader {
+            msgtype: message.msgtype,
+            instruction: u16::try_from(message.instruction).map_err(encode_failed)?,
+            data_size: u32::try_from(message.data.len()).map_err(encode_failed)?,
+            argument_count: u16::try_from(message.argument_count).map_err(encode_failed)?,
+            data_message_number: u32::try_from(message.data_message_number)
+                .map_err(encode_failed)?,
+            data_message_max: u32::try_from(message.data_message_max).map_err(encode_failed)?,
+            request_id: message.request_id,
+            compression: message.compression,
+        })
+    }
+
+    /// Writes the header to ```dst```.
+    pub fn encode(&self, dst: &mut BytesMut) {
+        dst.reserve(HEADER_LENGTH);
+        dst.put_u8(self.msgtype as u8);
+        dst.put_u16(self.instruction);
+        dst.put_u32(self.data_size);
+        dst.put_u16(self.argument_count);
+        dst.put_u32(self.data_message_number);
+        dst.put_u32(self.data_message_max);
+        dst.put_u64(self.request_id);
+        dst.put_u8(self.compression as u8);
+    }
+
+    /// Reads a header out of the first ```HEADER_LENGTH``` bytes of ```src```.
+    ///
+    /// Arguments:
+    /// src - The received bytes, at least ```HEADER_LENGTH``` long.
+    ///
+    /// Returns: the header, ```ReturnFlags::CommonMsgDecodeFailed``` if ```src``` is too short or
+    /// the message type or compression is unknown.
+    pub fn decode(mut src: &[u8]) -> Result<MessageHeader, ReturnFlags> {
+        if src.len() < HEADER_LENGTH {
+            return Err(ReturnFlags::CommonMsgDecodeFailed);
+        }
+        Ok(MessageHeader {
+            msgtype: MessageType::try_from(src.get_u8())?,
+            instruction: src.get_u16(),
+            data_size: src.get_u32(),
+            argument_count: src.get_u16(),
+            data_message_number: src.get_u32(),
+            data_message_max: src.get_u32(),
+            request_id: src.get_u64(),
+            compression: Compression::try_from(src.get_u8())?,
+        })
+    }
+
+    /// Builds the message made of the header and its ```data```.
+    pub fn into_message(self, data: Vec<u8>) -> Message {
+        Message {
+            msgtype: self.msgtype,
+            request_id: self.request_id,
+            instruction: self.instruction as i64,
+            argument_count: self.argument_count as usize,
+            data_message_number: self.data_message_number as usize,
+            data_message_max: self.data_message_max as usize,
+            compression: self.compression,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::message_builder::message_builder;
+
+    #[test]
+    fn test_message_header_golden_bytes() {
+        let mut message = message_builder(MessageType::DataTransfer, 10, 1, 2, 3, vec![0xab; 4]);
+        message.request_id = 0x0102030405060708;
+        message.compression = Compression::Zstd;
+
+        let header = MessageHeader::from_message(&message).unwrap();
+        let mut encoded = BytesMut::new();
+        header.encode(&mut encoded);
+        assert_eq!(
+            &encoded[..],
+            &[
+                0x01, /* messageType */
+                0x00, 0x0a, /* instruction */
+                0x00, 0x00, 0x00, 0x04, /* dataSize */
+                0x00, 0x01, /* argumentCount */
+                0x00, 0x00, 0x00, 0x02, /* dataMessageNumber */
+                0x00, 0x00, 0x00, 0x03, /* dataMessageMax */
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, /* requestId */
+                0x02, /* compression */
+            ][..]
+        );
+        assert_eq!(encoded.len(), HEADER_LENGTH);
+
+        let decoded = MessageHeader::decode(&encoded).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(decoded.into_message(vec![0xab; 4]), message);
+    }
+
+    #[test]
+    fn test_message_header_invalid() {
+        /* fields too large for the wire */
+        let message = message_builder(MessageType::Command, -1, 0, 0, 0, Vec::new());
+        assert_eq!(
+            MessageHeader::from_message(&message),
+            Err(ReturnFlags::CommonMsgEncodeFailed)
+        );
+        let message = message_builder(MessageType::Command, 1, 1 << 16, 0, 0, Vec::new());
+        assert_eq!(
+            MessageHeader::from_message(&message),
+            Err(ReturnFlags::CommonMsgEncodeFailed)
+        );
+
+        /* unknown message type and truncated header */
+        let mut encoded = [0u8; 26];
+        encoded[0] = 3;
+        assert_eq!(
+            MessageHeader::decode(&encoded),
+            Err(ReturnFlags::CommonMsgDecodeFailed)
+        );
+        let mut encoded = [0u8; 26];
+        encoded[25] = 3;
+        assert_eq!(
+            MessageHeader::decode(&encoded),
+            Err(ReturnFlags::CommonMsgDecodeFailed)
+        );
+        assert_eq!(
+            MessageHeader::decode(&[0u8; 25]),
+            Err(ReturnFlags::CommonMsgDecodeFailed)
+        );
+    }
+}
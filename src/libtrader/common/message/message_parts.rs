@@ -0,0 +1,218 @@
+use crate::common::message::message::Message;
+use crate::common::message::message_builder::message_builder;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// The largest ```data``` carried by a single part of a multi-part message, in bytes.
+pub static MAX_PART_DATA_LENGTH: usize = 64 * 1024;
+
+/// Splits a message into numbered parts.
+///
+/// Messages whose data fits in ```part_length``` are returned as is, with
+/// ```data_message_max``` left to ```0```. Otherwise every part carries the header of the
+/// message, ```data_message_max``` is the number of parts and ```data_message_number``` is the
+/// index of the part, starting from ```0```.
+///
+/// Arguments:
+/// message - The message to split.
+/// part_length - The largest data carried by one part, must not be ```0```.
+///
+/// Returns: the parts in sending order.
+///
+/// Example:
+/// ```rust
+///     for part in split_message(response, MAX_PART_DATA_LENGTH) {
+///         socket.send(part).await?;
+///     }
+/// ```
+pub fn split_message(message: Message, part_length: usize) -> Vec<Message> {
+    if message.data.len() <= part_length {
+        return vec![message];
+    }
+
+    let data_message_max = message.data.chunks(part_length).count();
+    message
+        .data
+        .chunks(part_length)
+        .enumerate()
+        .map(|(data_message_number, data)| {
+            let mut part = message_builder(
+                message.msgtype,
+                message.instruction,
+                message.argument_count,
+                data_message_number,
+                data_message_max,
+                data.to_vec(),
+            );
+            part.request_id = message.request_id;
+            part.compression = message.compression;
+            part
+        })
+        .collect()
+}
+
+/// Reassembles the parts of one multi-part message.
+///
+/// Parts must be pushed in the order they were received. A part skipping a number, a part
+/// arriving again or a part whose header disagrees with the previous parts fails the transfer.
+///
+/// Example:
+/// ```rust
+///     let mut assembler = MessageAssembler::default();
+///     for part in parts {
+///         if let Some(message) = assembler.push(part)? {
+///             handle(message);
+///         }
+///     }
+/// ```
+#[derive(Debug, Default)]
+pub struct MessageAssembler {
+    message: Option<Message>,
+}
+
+impl MessageAssembler {
+    /// Adds a received part.
+    ///
+    /// Arguments:
+    /// part - The received part, or a message that was not split.
+    ///
+    /// Returns: the whole message once its last part is pushed, with ```data_message_number```
+    /// and ```data_message_max``` reset to ```0```. ```ReturnFlags::CommonMsgPartMissing```,
+    /// ```ReturnFlags::CommonMsgPartOutOfOrder``` or ```ReturnFlags::CommonMsgPartInvalid``` on
+    /// error, after which the assembler is empty again.
+    pub fn push(&mut self, part: Message) -> Result<Option<Message>, ReturnFlags> {
+        let previous = self.message.take();
+
+        /* messages that were not split */
+        if part.data_message_max == 0 {
+            return match previous {
+                Some(_) => Err(ReturnFlags::CommonMsgPartMissing),
+                None => Ok(Some(part)),
+            };
+        }
+        if part.data_message_number >= part.data_message_max {
+            return Err(ReturnFlags::CommonMsgPartInvalid);
+        }
+
+        let message = match previous {
+            None => {
+                if part.data_message_number != 0 {
+                    return Err(ReturnFlags::CommonMsgPartMissing);
+                }
+                part
+            }
+            Some(mut message) => {
+                if message.msgtype != part.msgtype
+                    || message.request_id != part.request_id
+                    || message.instruction != part.instruction
+                    || message.argument_count != part.argument_count
+                    || message.data_message_max != part.data_message_max
+                    || message.compression != part.compression
+                {
+                    return Err(ReturnFlags::CommonMsgPartInvalid);
+                }
+
+                let expected = message.data_message_number + 1;
+                if part.data_message_number < expected {
+                    return Err(ReturnFlags::CommonMsgPartOutOfOrder);
+                } else if part.data_message_number > expected {
+                    return Err(ReturnFlags::CommonMsgPartMissing);
+                }
+                message.data_message_number = part.data_message_number;
+                message.data.extend_from_slice(&part.data);
+                message
+            }
+        };
+
+        if message.data_message_number + 1 == message.data_message_max {
+            let mut message = message;
+            message.data_message_number = 0;
+            message.data_message_max = 0;
+            Ok(Some(message))
+        } else {
+            self.message = Some(message);
+            Ok(None)
+        }
+    }
+
+    /// Returns whether a transfer is partially received.
+    pub fn is_pending(&self) -> bool {
+        self.message.is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::message_type::MessageType;
+
+    fn sample_message(data: Vec<u8>) -> Message {
+        let mut message = message_builder(MessageType::DataTransfer, 11, 1, 0, 0, data);
+        message.request_id = 42;
+        message
+    }
+
+    #[test]
+    fn test_message_parts_roundtrip() {
+        let data: Vec<u8> = (0..2500u32).map(|x| x as u8).collect();
+        let parts = split_message(sample_message(data.clone()), 1000);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[2].data_message_number, 2);
+        assert_eq!(parts[2].data_message_max, 3);
+        assert_eq!(parts[2].request_id, 42);
+
+        let mut assembler = MessageAssembler::default();
+        let mut parts = parts.into_iter();
+        assert_eq!(assembler.push(parts.next().unwrap()), Ok(None));
+        assert_eq!(assembler.push(parts.next().unwrap()), Ok(None));
+        assert!(assembler.is_pending());
+        assert_eq!(
+            assembler.push(parts.next().unwrap()),
+            Ok(Some(sample_message(data)))
+        );
+        assert!(!assembler.is_pending());
+
+        /* small messages are left whole */
+        let parts = split_message(sample_message(vec![1, 2, 3]), 1000);
+        assert_eq!(parts, vec![sample_message(vec![1, 2, 3])]);
+        assert_eq!(
+            assembler.push(sample_message(vec![1, 2, 3])),
+            Ok(Some(sample_message(vec![1, 2, 3])))
+        );
+    }
+
+    #[test]
+    fn test_message_parts_missing_and_out_of_order() {
+        let mut parts = split_message(sample_message(vec![0u8; 4000]), 1000);
+
+        /* skipped part */
+        let mut assembler = MessageAssembler::default();
+        assert_eq!(assembler.push(parts[0].clone()), Ok(None));
+        assert_eq!(
+            assembler.push(parts[2].clone()),
+            Err(ReturnFlags::CommonMsgPartMissing)
+        );
+        assert!(!assembler.is_pending());
+
+        /* transfer not starting at the first part */
+        assert_eq!(
+            assembler.push(parts[1].clone()),
+            Err(ReturnFlags::CommonMsgPartMissing)
+        );
+
+        /* repeated part */
+        assert_eq!(assembler.push(parts[0].clone()), Ok(None));
+        assert_eq!(assembler.push(parts[1].clone()), Ok(None));
+        assert_eq!(
+            assembler.push(parts[1].clone()),
+            Err(ReturnFlags::CommonMsgPartOutOfOrder)
+        );
+
+        /* part of another transfer */
+        parts[1].instruction = 10;
+        assert_eq!(assembler.push(parts[0].clone()), Ok(None));
+        assert_eq!(
+            assembler.push(parts[1].clone()),
+            Err(ReturnFlags::CommonMsgPartInvalid)
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::common::message::compression::Compression;
+use crate::common::message::inst::{CommandInst, DataTransferInst};
+use crate::common::message::message::Message;
+use crate::common::message::message_type::MessageType;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Expected shape of the messages carrying one request instruction.
+///
+/// Requests are never split nor compressed, so their ```data_message_number``` and
+/// ```data_message_max``` are always ```0```.
+///
+/// Members:
+/// msgtype - The ```MessageType``` of the request.
+/// instruction - The instruction of the request.
+/// argument_count - The exact argument count of the request.
+/// min_data_length - The shortest payload allowed, in bytes.
+/// max_data_length - The longest payload allowed, in bytes.
+/// requires_auth - Whether the request carries a JWT token that must be valid.
+/// invalid_flag - The ```ReturnFlags``` answered for messages not matching the schema.
+#[derive(PartialEq, Debug)]
+pub struct MessageSchema {
+    pub msgtype: MessageType,
+    pub instruction: i64,
+    pub argument_count: usize,
+    pub min_data_length: usize,
+    pub max_data_length: usize,
+    pub requires_auth: bool,
+    pub invalid_flag: ReturnFlags,
+}
+
+/// Schema of every request a client may send.
+///
+/// Payload lengths are those of the bincode encoded ```Request```: a 4 bytes variant tag
+/// followed by the arguments, strings and byte vectors being prefixed by an 8 bytes length.
+pub static REQUEST_SCHEMAS: &[MessageSchema] = &[
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::Hello as i64,
+        argument_count: 3,
+        min_data_length: 20,
+        max_data_length: 256,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::GenHashSalt as i64,
+        argument_count: 0,
+        min_data_length: 4,
+        max_data_length: 4,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::GetEmailSalt as i64,
+        argument_count: 1,
+        min_data_length: 13,
+        max_data_length: 512,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::GetPasswordSalt as i64,
+        argument_count: 1,
+        min_data_length: 13,
+        max_data_length: 512,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::Register as i64,
+        argument_count: 5,
+        min_data_length: 45,
+        max_data_length: 4096,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerRegisterInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::LoginMethod1 as i64,
+        argument_count: 3,
+        min_data_length: 29,
+        max_data_length: 2048,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerLoginInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::DataTransfer,
+        instruction: DataTransferInst::GetUserPortfolio as i64,
+        argument_count: 1,
+        min_data_length: 13,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerRetrievePortfolioInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::DataTransfer,
+        instruction: DataTransferInst::GetUserTransactionHist as i64,
+        argument_count: 1,
+        min_data_length: 13,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerRetrieveTransactionInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::DataTransfer,
+        instruction: DataTransferInst::GetAssetValue as i64,
+        argument_count: 3,
+        min_data_length: 29,
+        max_data_length: 512,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerGetAssetDataInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::PurchaseAsset as i64,
+        argument_count: 3,
+        min_data_length: 28,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerPurchaseAssetInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::SellAsset as i64,
+        argument_count: 3,
+        min_data_length: 28,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerSellAssetInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::Subscribe as i64,
+        argument_count: 1,
+        min_data_length: 12,
+        max_data_length: 16 * 1024,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::Unsubscribe as i64,
+        argument_count: 1,
+        min_data_length: 12,
+        max_data_length: 16 * 1024,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::Ping as i64,
+        argument_count: 0,
+        min_data_length: 4,
+        max_data_length: 4,
+        requires_auth: false,
+        invalid_flag: ReturnFlags::ServerHandleDataRcvdInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::PlaceOrder as i64,
+        argument_count: 9,
+        min_data_length: 61,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerPlaceOrderInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::PlaceOrderGroup as i64,
+        argument_count: 3,
+        min_data_length: 24,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerPlaceOrderGroupInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::DataTransfer,
+        instruction: DataTransferInst::GetUserOpenOrders as i64,
+        argument_count: 1,
+        min_data_length: 13,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerRetrieveOrdersInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::DataTransfer,
+        instruction: DataTransferInst::GetUserOrderHistory as i64,
+        argument_count: 1,
+        min_data_length: 13,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerRetrieveOrdersInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::CancelOrder as i64,
+        argument_count: 2,
+        min_data_length: 20,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerCancelOrderInvMsg,
+    },
+    MessageSchema {
+        msgtype: MessageType::Command,
+        instruction: CommandInst::AmendOrder as i64,
+        argument_count: 5,
+        min_data_length: 23,
+        max_data_length: 4096,
+        requires_auth: true,
+        invalid_flag: ReturnFlags::ServerAmendOrderInvMsg,
+    },
+];
+
+impl MessageSchema {
+    /// Checks the header and payload length of a message against the schema.
+    ///
+    /// Arguments:
+    /// message - The message to check.
+    ///
+    /// Returns: nothing on success, the ```invalid_flag``` of the schema on error.
+    pub fn check(&self, message: &Message) -> Result<(), ReturnFlags> {
+        if message.msgtype != self.msgtype
+            || message.instruction != self.instruction
+            || message.argument_count != self.argument_count
+            || message.data_message_number != 0
+            || message.data_message_max != 0
+            || message.compression != Compression::None
+            || message.data.len() < self.min_data_length
+            || message.data.len() > self.max_data_length
+        {
+            return Err(self.invalid_flag);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the schema of the request sent with ```msgtype``` and ```instruction```, if any.
+pub fn request_schema(msgtype: MessageType, instruction: i64) -> Option<&'static MessageSchema> {
+    REQUEST_SCHEMAS
+        .iter()
+        .find(|schema| schema.msgtype == msgtype && schema.instruction == instruction)
+}
+
+/// Validates a received request message against its schema.
+///
+/// Arguments:
+/// message - The received message.
+///
+/// Returns: the schema of the request on success, ```ReturnFlags::ServerHandleDataRcvdInvMsg```
+/// for unknown instructions, the ```invalid_flag``` of the schema for malformed requests.
+///
+/// Example:
+/// ```rust
+///     let schema = validate_request_message(&client_msg)?;
+///     if schema.requires_auth { /* verify JWT token */ }
+/// ```
+pub fn validate_request_message(message: &Message) -> Result<&'static MessageSchema, ReturnFlags> {
+    let schema = request_schema(message.msgtype, message.instruction)
+        .ok_or(ReturnFlags::ServerHandleDataRcvdInvMsg)?;
+    schema.check(message)?;
+    Ok(schema)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::request::{
+        sample_requests, LoginRequest, RegisterRequest, Request,
+    };
+
+    #[test]
+    fn test_schema_accepts_requests() {
+        for request in sample_requests() {
+            let schema = validate_request_message(&request.to_message()).unwrap();
+            assert_eq!(schema.requires_auth, request.auth_token().is_some());
+        }
+    }
+
+    #[test]
+    fn test_schema_rejects_messages() {
+        /* unknown instruction */
+        let mut message = Request::Ping.to_message();
+        message.instruction = 0;
+        assert_eq!(
+            validate_request_message(&message),
+            Err(ReturnFlags::ServerHandleDataRcvdInvMsg)
+        );
+
+        /* wrong argument count */
+        let mut message = Request::LoginNormal(LoginRequest::default()).to_message();
+        message.argument_count = 2;
+        assert_eq!(
+            validate_request_message(&message),
+            Err(ReturnFlags::ServerLoginInvMsg)
+        );
+
+        /* empty payload */
+        let mut message = Request::Register(RegisterRequest::default()).to_message();
+        message.data.clear();
+        assert_eq!(
+            validate_request_message(&message),
+            Err(ReturnFlags::ServerRegisterInvMsg)
+        );
+
+        /* oversized payload */
+        let message = Request::GetUserPortfolio("x".repeat(8192)).to_message();
+        assert_eq!(
+            validate_request_message(&message),
+            Err(ReturnFlags::ServerRetrievePortfolioInvMsg)
+        );
+
+        /* split request */
+        let mut message = Request::GenHashSalt.to_message();
+        message.data_message_max = 2;
+        assert_eq!(
+            validate_request_message(&message),
+            Err(ReturnFlags::ServerHandleDataRcvdInvMsg)
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+use crate::common::misc::return_flags::ReturnFlags;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
 pub enum MessageType {
     Command = 0,
     DataTransfer = 1,
@@ -12,6 +14,19 @@ impl std::fmt::Display for MessageType {
     }
 }
 
+impl std::convert::TryFrom<u8> for MessageType {
+    type Error = ReturnFlags;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MessageType::Command),
+            1 => Ok(MessageType::DataTransfer),
+            2 => Ok(MessageType::ServerReturn),
+            _ => Err(ReturnFlags::CommonMsgDecodeFailed),
+        }
+    }
+}
+
 impl Default for MessageType {
     fn default() -> Self {
         MessageType::Command
@@ -1,4 +1,13 @@
+pub mod bounded_decode;
+pub mod compression;
+pub mod hello;
 pub mod inst;
 pub mod message;
 pub mod message_builder;
+pub mod message_codec;
+pub mod message_header;
+pub mod message_parts;
+pub mod message_schema;
 pub mod message_type;
+pub mod request;
+pub mod response;
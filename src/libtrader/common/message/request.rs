@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::common::account::order::{Order, OrderType, TimeInForce};
+use crate::common::account::order_group::GroupType;
+use crate::common::message::bounded_decode::bounded_deserialize;
+use crate::common::message::hello::Hello;
+use crate::common::message::inst::{CommandInst, DataTransferInst};
+use crate::common::message::message::Message;
+use crate::common::message::message_builder::message_builder;
+use crate::common::message::message_type::MessageType;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Arguments of a ```Request::Register```.
+///
+/// Members:
+/// username - The username of the new account.
+/// email_hash - The client hashed email.
+/// email_client_salt - The full salt used for the client email hash.
+/// password_hash - The client hashed password.
+/// password_client_salt - The full salt used for the client password hash.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default, Clone)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email_hash: Vec<u8>,
+    pub email_client_salt: Vec<u8>,
+    pub password_hash: Vec<u8>,
+    pub password_client_salt: Vec<u8>,
+}
+
+/// Arguments of a ```Request::LoginNormal```.
+///
+/// Members:
+/// username - The username of the account.
+/// hashed_email - The email hashed with the stored client email salt.
+/// hashed_password - The password hashed with the stored client password salt.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug, Default, Clone)]
+pub struct LoginRequest {
+    pub username: String,
+    pub hashed_email: Vec<u8>,
+    pub hashed_password: Vec<u8>,
+}
+
+/// Arguments of a ```Request::GetAssetData```.
+///
+/// Members:
+/// symbol - The symbol of the stock.
+/// start_epoch - The first time epoch to retrieve.
+/// end_epoch - The last time epoch to retrieve.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct AssetDataRequest {
+    pub symbol: String,
+    pub start_epoch: i64,
+    pub end_epoch: i64,
+}
+
+/// Arguments of a ```Request::PurchaseAsset```.
+///
+/// Members:
+/// auth_jwt - The JWT token of the account buying.
+/// symbol - The symbol of the stock to buy.
+/// quantity - The number of shares to buy.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct PurchaseAssetRequest {
+    pub auth_jwt: String,
+    pub symbol: String,
+    pub quantity: i64,
+}
+
+/// Arguments of a ```Request::SellAsset```.
+///
+/// Members:
+/// auth_jwt - The JWT token of the account selling.
+/// symbol - The symbol of the stock to sell.
+/// quantity - The number of shares to sell.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct SellAssetRequest {
+    pub auth_jwt: String,
+    pub symbol: String,
+    pub quantity: i64,
+}
+
+/// Arguments of a ```Request::PlaceOrder```.
+///
+/// Members:
+/// auth_jwt - The JWT token of the account placing the order.
+/// symbol - The symbol of the stock to trade.
+/// is_buy - Whether the order buys or sells shares.
+/// order_type - The kind of the order.
+/// quantity - The number of shares to trade.
+/// limit_price - The worst price of one share the order accepts, for limit and stop-limit
+/// orders.
+/// stop_price - The price triggering the order, for stop and stop-limit orders.
+/// trail_amount - The distance of the stop to the best price seen, for trailing stops.
+/// time_in_force - How long the order stays open.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct PlaceOrderRequest {
+    pub auth_jwt: String,
+    pub symbol: String,
+    pub is_buy: bool,
+    pub order_type: OrderType,
+    pub quantity: i64,
+    pub limit_price: f64,
+    pub stop_price: f64,
+    pub trail_amount: f64,
+    pub time_in_force: TimeInForce,
+}
+
+/// Arguments of a ```Request::PlaceOrderGroup```.
+///
+/// Members:
+/// auth_jwt - The JWT token of the account placing the orders.
+/// group_type - The kind of the group.
+/// orders - The terms of the orders of the group, see ```acc_place_order_group()```.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct PlaceOrderGroupRequest {
+    pub auth_jwt: String,
+    pub group_type: GroupType,
+    pub orders: Vec<Order>,
+}
+
+/// Arguments of a ```Request::CancelOrder```.
+///
+/// Members:
+/// auth_jwt - The JWT token of the account owning the order.
+/// order_id - The id of the order to cancel.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct CancelOrderRequest {
+    pub auth_jwt: String,
+    pub order_id: i64,
+}
+
+/// Arguments of a ```Request::AmendOrder```.
+///
+/// Members left to ```None``` are not changed.
+///
+/// Members:
+/// auth_jwt - The JWT token of the account owning the order.
+/// order_id - The id of the order to amend.
+/// quantity - The new number of shares of the order, filled shares included.
+/// limit_price - The new limit price, for limit and stop-limit orders.
+/// stop_price - The new stop price, for stop and stop-limit orders.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct AmendOrderRequest {
+    pub auth_jwt: String,
+    pub order_id: i64,
+    pub quantity: Option<i64>,
+    pub limit_price: Option<f64>,
+    pub stop_price: Option<f64>,
+}
+
+/// A client request, one variant per instruction.
+///
+/// The request is carried bincode encoded in the ```data``` of a ```Message```, the header of
+/// the message is derived from the variant. Both server and client build and parse requests
+/// through this type only.
+/// The payload is little-endian and tagged by the index of the variant, see the payload
+/// encoding in ```docs/protocol.tex```. New variants go last, to keep the tags of the others.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum Request {
+    Hello(Hello),
+    GenHashSalt,
+    GetEmailSalt(String),
+    GetPasswordSalt(String),
+    Register(RegisterRequest),
+    LoginNormal(LoginRequest),
+    GetUserPortfolio(String),
+    GetUserTransactionHist(String),
+    GetAssetData(AssetDataRequest),
+    PurchaseAsset(PurchaseAssetRequest),
+    SellAsset(SellAssetRequest),
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    Ping,
+    PlaceOrder(PlaceOrderRequest),
+    PlaceOrderGroup(PlaceOrderGroupRequest),
+    GetUserOpenOrders(String),
+    GetUserOrderHistory(String),
+    CancelOrder(CancelOrderRequest),
+    AmendOrder(AmendOrderRequest),
+}
+
+impl Request {
+    /// Returns the ```MessageType``` the request is sent with.
+    pub fn msgtype(&self) -> MessageType {
+        match self {
+            Request::GetUserPortfolio(_)
+            | Request::GetUserTransactionHist(_)
+            | Request::GetAssetData(_)
+            | Request::GetUserOpenOrders(_)
+            | Request::GetUserOrderHistory(_) => MessageType::DataTransfer,
+            _ => MessageType::Command,
+        }
+    }
+
+    /// Returns the instruction the request is sent with.
+    pub fn instruction(&self) -> i64 {
+        match self {
+            Request::Hello(_) => CommandInst::Hello as i64,
+            Request::GenHashSalt => CommandInst::GenHashSalt as i64,
+            Request::GetEmailSalt(_) => CommandInst::GetEmailSalt as i64,
+            Request::GetPasswordSalt(_) => CommandInst::GetPasswordSalt as i64,
+            Request::Register(_) => CommandInst::Register as i64,
+            Request::LoginNormal(_) => CommandInst::LoginMethod1 as i64,
+            Request::GetUserPortfolio(_) => DataTransferInst::GetUserPortfolio as i64,
+            Request::GetUserTransactionHist(_) => DataTransferInst::GetUserTransactionHist as i64,
+            Request::GetAssetData(_) => DataTransferInst::GetAssetValue as i64,
+            Request::PurchaseAsset(_) => CommandInst::PurchaseAsset as i64,
+            Request::SellAsset(_) => CommandInst::SellAsset as i64,
+            Request::Subscribe(_) => CommandInst::Subscribe as i64,
+            Request::Unsubscribe(_) => CommandInst::Unsubscribe as i64,
+            Request::Ping => CommandInst::Ping as i64,
+            Request::PlaceOrder(_) => CommandInst::PlaceOrder as i64,
+            Request::PlaceOrderGroup(_) => CommandInst::PlaceOrderGroup as i64,
+            Request::GetUserOpenOrders(_) => DataTransferInst::GetUserOpenOrders as i64,
+            Request::GetUserOrderHistory(_) => DataTransferInst::GetUserOrderHistory as i64,
+            Request::CancelOrder(_) => CommandInst::CancelOrder as i64,
+            Request::AmendOrder(_) => CommandInst::AmendOrder as i64,
+        }
+    }
+
+    /// Returns the number of arguments the request carries.
+    pub fn argument_count(&self) -> usize {
+        match self {
+            Request::GenHashSalt | Request::Ping => 0,
+            Request::CancelOrder(_) => 2,
+            Request::Register(_) | Request::AmendOrder(_) => 5,
+            Request::PlaceOrder(_) => 9,
+            Request::Hello(_)
+            | Request::LoginNormal(_)
+            | Request::GetAssetData(_)
+            | Request::PurchaseAsset(_)
+            | Request::SellAsset(_)
+            | Request::PlaceOrderGroup(_) => 3,
+            _ => 1,
+        }
+    }
+
+    /// Returns the JWT token carried by the request, if any.
+    pub fn auth_token(&self) -> Option<&str> {
+        match self {
+            Request::GetUserPortfolio(auth_jwt)
+            | Request::GetUserTransactionHist(auth_jwt)
+            | Request::GetUserOpenOrders(auth_jwt)
+            | Request::GetUserOrderHistory(auth_jwt) => Some(auth_jwt),
+            Request::PurchaseAsset(purchase_req) => Some(&purchase_req.auth_jwt),
+            Request::SellAsset(sell_req) => Some(&sell_req.auth_jwt),
+            Request::PlaceOrder(order_req) => Some(&order_req.auth_jwt),
+            Request::PlaceOrderGroup(group_req) => Some(&group_req.auth_jwt),
+            Request::CancelOrder(cancel_req) => Some(&cancel_req.auth_jwt),
+            Request::AmendOrder(amend_req) => Some(&amend_req.auth_jwt),
+            _ => None,
+        }
+    }
+
+    /// Builds the ```Message``` carrying the request.
+    ///
+    /// Example:
+    /// ```rust
+    ///     socket.send(Request::GetUserPortfolio(auth_jwt).to_message()).await?;
+    /// ```
+    pub fn to_message(&self) -> Message {
+        message_builder(
+            self.msgtype(),
+            self.instruction(),
+            self.argument_count(),
+            0,
+            0,
+            bincode::serialize(self).unwrap(),
+        )
+    }
+
+    /// Decodes a request out of a received ```Message```.
+    ///
+    /// Fails if the data is not a valid request, or if the decoded request does not match the
+    /// header of the message.
+    ///
+    /// Arguments:
+    /// message - The received message.
+    ///
+    /// Returns: the decoded request, ```ReturnFlags::CommonMsgDecodeFailed``` on error.
+    pub fn from_message(message: &Message) -> Result<Request, ReturnFlags> {
+        let request: Request =
+            bounded_deserialize(&message.data).map_err(|_| ReturnFlags::CommonMsgDecodeFailed)?;
+
+        if request.msgtype() != message.msgtype || request.instruction() != message.instruction {
+            return Err(ReturnFlags::CommonMsgDecodeFailed);
+        }
+        Ok(request)
+    }
+}
+impl std::fmt::Display for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+
+/// Returns one well formed request of every kind, for tests.
+#[cfg(test)]
+pub fn sample_requests() -> Vec<Request> {
+    vec![
+        Request::Hello(Hello::new()),
+        Request::GenHashSalt,
+        Request::GetEmailSalt("n1ckn8me".to_string()),
+        Request::GetPasswordSalt("n1ckn8me".to_string()),
+        Request::Register(RegisterRequest {
+            username: "n1ckn8me".to_string(),
+            email_hash: vec![1u8; 64],
+            email_client_salt: vec![2u8; 64],
+            password_hash: vec![3u8; 64],
+            password_client_salt: vec![4u8; 64],
+        }),
+        Request::LoginNormal(LoginRequest {
+            username: "n1ckn8me".to_string(),
+            hashed_email: vec![1u8; 64],
+            hashed_password: vec![2u8; 64],
+        }),
+        Request::GetUserPortfolio("jwt".to_string()),
+        Request::GetUserTransactionHist("jwt".to_string()),
+        Request::GetAssetData(AssetDataRequest {
+            symbol: "AAPL".to_string(),
+            start_epoch: 0,
+            end_epoch: 1_000_000,
+        }),
+        Request::PurchaseAsset(PurchaseAssetRequest {
+            auth_jwt: "jwt".to_string(),
+            symbol: "AAPL".to_string(),
+            quantity: 10,
+        }),
+        Request::SellAsset(SellAssetRequest {
+            auth_jwt: "jwt".to_string(),
+            symbol: "AAPL".to_string(),
+            quantity: 5,
+        }),
+        Request::Subscribe(vec!["AAPL".to_string()]),
+        Request::Unsubscribe(vec![]),
+        Request::Ping,
+        Request::PlaceOrder(PlaceOrderRequest {
+            auth_jwt: "jwt".to_string(),
+            symbol: "AAPL".to_string(),
+            is_buy: false,
+            order_type: OrderType::StopLimit,
+            quantity: 10,
+            limit_price: 149.5,
+            stop_price: 150.25,
+            trail_amount: 0.0,
+            time_in_force: TimeInForce::Day,
+        }),
+        Request::PlaceOrderGroup(PlaceOrderGroupRequest {
+            auth_jwt: "jwt".to_string(),
+            group_type: GroupType::Oco,
+            orders: vec![Order::default(), Order::default()],
+        }),
+        Request::GetUserOpenOrders("jwt".to_string()),
+        Request::GetUserOrderHistory("jwt".to_string()),
+        Request::CancelOrder(CancelOrderRequest {
+            auth_jwt: "jwt".to_string(),
+            order_id: 1,
+        }),
+        Request::AmendOrder(AmendOrderRequest {
+            auth_jwt: "jwt".to_string(),
+            order_id: 1,
+            quantity: Some(20),
+            limit_price: Some(151.0),
+            stop_price: None,
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_request_message_roundtrip() {
+        let request = Request::LoginNormal(LoginRequest {
+            username: "n1ckn8me".to_string(),
+            hashed_email: vec![1u8; 64],
+            hashed_password: vec![2u8; 64],
+        });
+        let message = request.to_message();
+        assert_eq!(message.msgtype, MessageType::Command);
+        assert_eq!(message.instruction, CommandInst::LoginMethod1 as i64);
+        assert_eq!(message.argument_count, 3);
+        assert_eq!(Request::from_message(&message), Ok(request));
+    }
+
+    #[test]
+    fn test_request_payload_golden() {
+        let message = Request::CancelOrder(CancelOrderRequest {
+            auth_jwt: "jwt".to_string(),
+            order_id: 1,
+        })
+        .to_message();
+        assert_eq!(
+            message.data,
+            vec![
+                18, 0, 0, 0, /* tag */
+                3, 0, 0, 0, 0, 0, 0, 0, b'j', b'w', b't', /* auth_jwt */
+                1, 0, 0, 0, 0, 0, 0, 0, /* order_id */
+            ]
+        );
+
+        let message = Request::AmendOrder(AmendOrderRequest {
+            auth_jwt: String::new(),
+            order_id: 2,
+            quantity: Some(20),
+            limit_price: Some(151.0),
+            stop_price: None,
+        })
+        .to_message();
+        assert_eq!(
+            message.data,
+            vec![
+                19, 0, 0, 0, /* tag */
+                0, 0, 0, 0, 0, 0, 0, 0, /* auth_jwt */
+                2, 0, 0, 0, 0, 0, 0, 0, /* order_id */
+                1, 20, 0, 0, 0, 0, 0, 0, 0, /* quantity */
+                1, 0, 0, 0, 0, 0, 0xe0, 0x62, 0x40, /* limit_price */
+                0,    /* stop_price */
+            ]
+        );
+        assert_eq!(
+            bincode::serialize(&Request::Ping).unwrap(),
+            vec![13, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_request_from_message_invalid() {
+        /* garbage payload */
+        let message = message_builder(
+            MessageType::Command,
+            CommandInst::Register as i64,
+            5,
+            0,
+            0,
+            b"{\"username\": 1}".to_vec(),
+        );
+        assert_eq!(
+            Request::from_message(&message),
+            Err(ReturnFlags::CommonMsgDecodeFailed)
+        );
+
+        /* payload disagreeing with the header */
+        let mut message = Request::GetEmailSalt("n1ckn8me".to_string()).to_message();
+        message.instruction = CommandInst::GetPasswordSalt as i64;
+        assert_eq!(
+            Request::from_message(&message),
+            Err(ReturnFlags::CommonMsgDecodeFailed)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::account::fill::Fill;
+use crate::common::account::order::Order;
+use crate::common::account::order_group::OrderGroup;
+use crate::common::account::portfolio::Portfolio;
+use crate::common::account::transaction::Transaction;
+use crate::common::generic::stock_val::StockVal;
+use crate::common::message::bounded_decode::bounded_deserialize;
+use crate::common::message::hello::Hello;
+use crate::common::message::inst::{CommandInst, DataTransferInst};
+use crate::common::message::message::Message;
+use crate::common::message::message_builder::message_builder;
+use crate::common::message::message_type::MessageType;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// A server response, one variant per answered instruction.
+///
+/// Every failed instruction is answered with ```Response::Error```, carrying the
+/// ```ReturnFlags``` describing the failure and a detail message meant for humans.
+/// ```Response::Quote``` answers no request, it is pushed to subscribed clients with a
+/// ```request_id``` of ```0```.
+///
+/// The response is carried bincode encoded in the ```data``` of a ```Message```, the header of
+/// the message is derived from the variant.
+/// The payload is encoded as for ```Request```, see the payload encoding in
+/// ```docs/protocol.tex```.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum Response {
+    Hello(Hello),
+    HashSalt(Vec<u8>),
+    EmailSalt(Vec<u8>),
+    PasswordSalt(Vec<u8>),
+    Registered,
+    LoggedIn(String),
+    Portfolio(Portfolio),
+    Transactions(Vec<Transaction>),
+    AssetData(Vec<StockVal>),
+    AssetPurchased(Fill),
+    AssetSold(Fill),
+    Subscribed(Vec<String>),
+    Unsubscribed(Vec<String>),
+    Quote(String, StockVal),
+    Pong,
+    OrderPlaced(Order),
+    OrderGroupPlaced(OrderGroup),
+    OpenOrders(Vec<Order>),
+    OrderHistory(Vec<Order>),
+    OrderCancelled(Order),
+    OrderAmended(Order),
+    Error(ReturnFlags, String),
+}
+
+impl Response {
+    /// Returns the ```MessageType``` the response is sent with.
+    pub fn msgtype(&self) -> MessageType {
+        match self {
+            Response::HashSalt(_)
+            | Response::EmailSalt(_)
+            | Response::PasswordSalt(_)
+            | Response::Portfolio(_)
+            | Response::Transactions(_)
+            | Response::AssetData(_)
+            | Response::OpenOrders(_)
+            | Response::OrderHistory(_)
+            | Response::Quote(_, _) => MessageType::DataTransfer,
+            _ => MessageType::ServerReturn,
+        }
+    }
+
+    /// Returns the instruction the response answers, ```0``` for errors.
+    pub fn instruction(&self) -> i64 {
+        match self {
+            Response::Hello(_) => CommandInst::Hello as i64,
+            Response::HashSalt(_) => CommandInst::GenHashSalt as i64,
+            Response::EmailSalt(_) => CommandInst::GetEmailSalt as i64,
+            Response::PasswordSalt(_) => CommandInst::GetPasswordSalt as i64,
+            Response::Registered => CommandInst::Register as i64,
+            Response::LoggedIn(_) => CommandInst::LoginMethod1 as i64,
+            Response::Portfolio(_) => DataTransferInst::GetUserPortfolio as i64,
+            Response::Transactions(_) => DataTransferInst::GetUserTransactionHist as i64,
+            Response::AssetData(_) => DataTransferInst::GetAssetValue as i64,
+            Response::AssetPurchased(_) => CommandInst::PurchaseAsset as i64,
+            Response::AssetSold(_) => CommandInst::SellAsset as i64,
+            Response::Subscribed(_) => CommandInst::Subscribe as i64,
+            Response::Unsubscribed(_) => CommandInst::Unsubscribe as i64,
+            Response::Quote(_, _) => DataTransferInst::PushQuote as i64,
+            Response::Pong => CommandInst::Ping as i64,
+            Response::OrderPlaced(_) => CommandInst::PlaceOrder as i64,
+            Response::OrderGroupPlaced(_) => CommandInst::PlaceOrderGroup as i64,
+            Response::OpenOrders(_) => DataTransferInst::GetUserOpenOrders as i64,
+            Response::OrderHistory(_) => DataTransferInst::GetUserOrderHistory as i64,
+            Response::OrderCancelled(_) => CommandInst::CancelOrder as i64,
+            Response::OrderAmended(_) => CommandInst::AmendOrder as i64,
+            Response::Error(_, _) => 0,
+        }
+    }
+
+    /// Builds the ```Message``` carrying the response.
+    ///
+    /// Example:
+    /// ```rust
+    ///     socket.send(Response::Registered.to_message()).await?;
+    /// ```
+    pub fn to_message(&self) -> Message {
+        let argument_count = match self {
+            Response::Registered | Response::Pong => 0,
+            Response::Hello(_) => 3,
+            Response::Quote(_, _) => 2,
+            _ => 1,
+        };
+        message_builder(
+            self.msgtype(),
+            self.instruction(),
+            argument_count,
+            0,
+            0,
+            bincode::serialize(self).unwrap(),
+        )
+    }
+
+    /// Decodes a response out of a received ```Message```.
+    ///
+    /// Arguments:
+    /// message - The received message.
+    ///
+    /// Returns: the decoded response, ```ReturnFlags::CommonMsgDecodeFailed``` on error.
+    pub fn from_message(message: &Message) -> Result<Response, ReturnFlags> {
+        let response: Response =
+            bounded_deserialize(&message.data).map_err(|_| ReturnFlags::CommonMsgDecodeFailed)?;
+
+        if response.msgtype() != message.msgtype || response.instruction() != message.instruction {
+            return Err(ReturnFlags::CommonMsgDecodeFailed);
+        }
+        Ok(response)
+    }
+}
+impl std::fmt::Display for Response {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#?}", self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_response_payload_golden() {
+        /* return flags are tagged by their index, not their value */
+        let message =
+            Response::Error(ReturnFlags::LibtraderInitFailed, "x".to_string()).to_message();
+        assert_eq!(
+            message.data,
+            vec![
+                21, 0, 0, 0, /* tag */
+                2, 0, 0, 0, /* flag */
+                1, 0, 0, 0, 0, 0, 0, 0, b'x', /* message */
+            ]
+        );
+    }
+}
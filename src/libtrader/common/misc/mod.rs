@@ -1,3 +1,2 @@
-pub mod assert_msg;
 pub mod return_flags;
 pub mod servers_pool;
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ReturnFlags {
     LibtraderInitClientConnect = 1,
     LibtraderInitLogFailed = 2,
@@ -67,6 +67,56 @@ pub enum ReturnFlags {
 
     ClientTlsReadError = 47,
     ClientWaitAndReadBranched = 48,
+
+    CommonMsgDecodeFailed = 49,
+
+    CommonProtocolVersionMismatch = 50,
+    ServerHelloNotReceived = 51,
+    ClientHelloFailed = 52,
+
+    CommonMsgPartMissing = 53,
+    CommonMsgPartOutOfOrder = 54,
+    CommonMsgPartInvalid = 55,
+
+    ServerSubscribeInvSymbol = 56,
+    ServerSubscribeLimitReached = 57,
+    ClientSubscribeFailed = 58,
+
+    ServerConnIdleTimeout = 59,
+    ClientConnPeerTimeout = 60,
+
+    ServerDbQueryFailed = 61,
+
+    CommonMsgEncodeFailed = 62,
+    CommonMsgDecompressFailed = 63,
+
+    ServerPurchaseAssetFailed = 64,
+    ClientPurchaseAssetFailed = 65,
+
+    ServerSellAssetInvMsg = 66,
+    ServerSellAssetOversold = 67,
+    ServerSellAssetFailed = 68,
+    ClientSellAssetFailed = 69,
+
+    ServerAccInsufficientCash = 70,
+
+    ServerPlaceOrderInvMsg = 71,
+    ServerPlaceOrderFailed = 72,
+    ClientPlaceOrderFailed = 73,
+
+    ServerPlaceOrderGroupInvMsg = 74,
+    ClientPlaceOrderGroupFailed = 75,
+
+    ServerRetrieveOrdersInvMsg = 76,
+    ClientRetrieveOrdersFailed = 77,
+    ServerCancelOrderInvMsg = 78,
+    ClientCancelOrderFailed = 79,
+    ServerAmendOrderInvMsg = 80,
+    ClientAmendOrderFailed = 81,
+    ServerOrderNotFound = 82,
+    ServerOrderNotOpen = 83,
+
+    ServerPayDividendInvAmount = 84,
 }
 impl std::fmt::Display for ReturnFlags {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -2,4 +2,5 @@ pub mod account;
 pub mod generic;
 pub mod message;
 pub mod misc;
+pub mod network;
 pub mod sessions;
@@ -0,0 +1,17 @@
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// A byte stream messages can be exchanged over.
+///
+/// Implemented for every ```AsyncRead + AsyncWrite``` stream, such as TLS over TCP, plain TCP,
+/// Unix sockets or ```tokio::io::duplex``` pairs. Server and client code take any transport,
+/// only the initializers pick a concrete one.
+///
+/// Example:
+/// ```rust
+///     let mut socket = Framed::new(tls_stream, MessageCodec::new());
+///     let (client, server) = tokio::io::duplex(4096);
+///     let mut socket = Framed::new(client, MessageCodec::new());
+/// ```
+pub trait Transport: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + 'static> Transport for T {}
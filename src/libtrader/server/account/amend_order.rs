@@ -0,0 +1,319 @@
+use log::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::common::account::order::{Order, OrderStatus, OrderType};
+use crate::common::message::request::AmendOrderRequest;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::account::place_order::{check_coverage, fill_placed};
+use crate::server::db::cmd::get_orders::{get_order, get_order_for_update};
+use crate::server::db::cmd::get_stock::get_stock_from_db_latest;
+use crate::server::db::cmd::update_order::{update_order_terms, update_order_trigger};
+use crate::server::engine::engine_conn::{
+    begin_transaction, commit_transaction, connect_trade_conn,
+};
+use crate::server::engine::trigger_order::trigger_order;
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Amends the price or quantity of an open order of an authorized account.
+///
+/// The amended order keeps its place in the book and the shares it filled already. It is
+/// checked for coverage like a new order, then filled against the latest quote of the stock
+/// as far as the quote allows, see ```fill_placed()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use for reads.
+/// amend_req - The id of the order and its new terms, with the JWT token of the account owning
+/// it.
+///
+/// Returns: the amended ```Order``` as stored on success, ```ReturnFlags::ServerOrderNotFound```
+/// if the account has no order with the id, ```ReturnFlags::ServerOrderNotOpen``` if the
+/// order is filled or closed already, ```ReturnFlags::ServerAmendOrderInvMsg``` if the new
+/// terms are invalid, ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let order = acc_amend_order(&sql_conn, &amend_req).await?;
+/// ```
+pub async fn acc_amend_order(
+    sql_conn: &tokio_postgres::Client,
+    amend_req: &AmendOrderRequest,
+) -> Result<Order, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(amend_req.auth_jwt.clone()) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_AMEND_ORDER_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+
+    let order = match get_order(sql_conn, amend_req.order_id).await? {
+        Some((user_id, order)) if user_id == token.user_id => order,
+        _ => return Err(ReturnFlags::ServerOrderNotFound),
+    };
+    /* invalid terms are refused before opening a database transaction */
+    amend_terms(&order, amend_req)?;
+
+    let quote = get_stock_from_db_latest(sql_conn, &order.stock_symbol)
+        .await
+        .ok();
+    let mut trade_conn = connect_trade_conn().await?;
+
+    /* the order may have been filled meanwhile, the terms are applied to the stored order */
+    let db_transaction = begin_transaction(&mut trade_conn).await?;
+    let order = match get_order_for_update(&db_transaction, amend_req.order_id).await? {
+        Some((_, order)) => order,
+        None => return Err(ReturnFlags::ServerOrderNotFound),
+    };
+    let mut order = amend_terms(&order, amend_req)?;
+
+    /* only the shares left to fill need to be covered, pending exits sell shares their entry
+     * has not bought yet */
+    if order.status != OrderStatus::Pending {
+        let left = Order {
+            stock_amount: order.stock_amount - order.stock_filled,
+            ..order.clone()
+        };
+        check_coverage(&db_transaction, token.user_id, &left, quote.as_ref()).await?;
+    }
+    update_order_terms(&db_transaction, &order).await?;
+
+    /* a stop moved past the quote is triggered at once, pending exits never are */
+    if let Some(quote) = &quote {
+        if trigger_order(&mut order, quote) {
+            update_order_trigger(&db_transaction, &order).await?;
+        }
+    }
+    commit_transaction(db_transaction).await?;
+
+    /* pending exits wait for their entry */
+    if order.status != OrderStatus::Open {
+        return Ok(order);
+    }
+    Ok(fill_placed(&mut trade_conn, order, quote.as_ref(), time_epoch).await)
+}
+
+/// Applies the terms of an amendment to an order, checking them.
+///
+/// Only the prices the type of the order uses can be amended, and the new quantity must leave
+/// shares to fill. The quantity of a pending exit is set by its entry, see
+/// ```resolve_group()```, and cannot be amended.
+///
+/// Arguments:
+/// order - The order to amend.
+/// amend_req - The new terms, ```None``` for the terms left unchanged.
+///
+/// Returns: the amended ```Order``` on success, ```ReturnFlags::ServerOrderNotOpen``` if the
+/// order is in a final state, ```ReturnFlags::ServerAmendOrderInvMsg``` if the new terms are
+/// invalid.
+fn amend_terms(order: &Order, amend_req: &AmendOrderRequest) -> Result<Order, ReturnFlags> {
+    if order.status.is_final() {
+        return Err(ReturnFlags::ServerOrderNotOpen);
+    }
+
+    let valid = |price: f64| price.is_finite() && price > 0.0;
+    let uses_stop = matches!(order.order_type, OrderType::Stop | OrderType::StopLimit);
+    if amend_req.quantity.is_none()
+        && amend_req.limit_price.is_none()
+        && amend_req.stop_price.is_none()
+    {
+        return Err(ReturnFlags::ServerAmendOrderInvMsg);
+    }
+
+    let mut amended = order.clone();
+    if let Some(quantity) = amend_req.quantity {
+        if quantity <= order.stock_filled || order.status == OrderStatus::Pending {
+            return Err(ReturnFlags::ServerAmendOrderInvMsg);
+        }
+        amended.stock_amount = quantity;
+    }
+    if let Some(limit_price) = amend_req.limit_price {
+        if !order.order_type.has_limit() || !valid(limit_price) {
+            return Err(ReturnFlags::ServerAmendOrderInvMsg);
+        }
+        amended.stock_price = limit_price;
+    }
+    if let Some(stop_price) = amend_req.stop_price {
+        if !uses_stop || !valid(stop_price) {
+            return Err(ReturnFlags::ServerAmendOrderInvMsg);
+        }
+        amended.stop_price = stop_price;
+    }
+    Ok(amended)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_amend_terms() {
+        let order = Order {
+            id: 7,
+            stock_symbol: "AAPL".to_string(),
+            order_type: OrderType::StopLimit,
+            stock_price: 99.0,
+            stock_amount: 10,
+            stock_filled: 4,
+            stop_price: 95.0,
+            ..Order::default()
+        };
+        let amend = |quantity, limit_price, stop_price| AmendOrderRequest {
+            auth_jwt: "jwt".to_string(),
+            order_id: 7,
+            quantity,
+            limit_price,
+            stop_price,
+        };
+
+        let amended = amend_terms(&order, &amend(Some(5), Some(101.0), None)).unwrap();
+        assert_eq!((amended.stock_amount, amended.stock_filled), (5, 4));
+        assert_eq!((amended.stock_price, amended.stop_price), (101.0, 95.0));
+        let amended = amend_terms(&order, &amend(None, None, Some(96.0))).unwrap();
+        assert_eq!((amended.stock_amount, amended.stop_price), (10, 96.0));
+
+        /* something must change, and leave shares to fill */
+        for (quantity, limit_price, stop_price) in [
+            (None, None, None),
+            (Some(4), None, None),
+            (None, Some(0.0), None),
+            (None, None, Some(f64::INFINITY)),
+        ] {
+            assert_eq!(
+                amend_terms(&order, &amend(quantity, limit_price, stop_price)),
+                Err(ReturnFlags::ServerAmendOrderInvMsg)
+            );
+        }
+
+        /* only the prices the type uses */
+        let limit = Order {
+            order_type: OrderType::Limit,
+            ..order.clone()
+        };
+        assert!(amend_terms(&limit, &amend(None, Some(98.0), None)).is_ok());
+        assert_eq!(
+            amend_terms(&limit, &amend(None, None, Some(96.0))),
+            Err(ReturnFlags::ServerAmendOrderInvMsg)
+        );
+        let trailing = Order {
+            order_type: OrderType::TrailingStop,
+            ..order.clone()
+        };
+        assert_eq!(
+            amend_terms(&trailing, &amend(None, Some(98.0), None)),
+            Err(ReturnFlags::ServerAmendOrderInvMsg)
+        );
+
+        let pending = Order {
+            status: OrderStatus::Pending,
+            ..order.clone()
+        };
+        assert!(amend_terms(&pending, &amend(None, Some(98.0), None)).is_ok());
+        assert_eq!(
+            amend_terms(&pending, &amend(Some(20), None, None)),
+            Err(ReturnFlags::ServerAmendOrderInvMsg)
+        );
+
+        let filled = Order {
+            status: OrderStatus::Filled,
+            ..order
+        };
+        assert_eq!(
+            amend_terms(&filled, &amend(Some(20), None, None)),
+            Err(ReturnFlags::ServerOrderNotOpen)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_amend_pending_exit() {
+        use crate::common::account::order::GroupRole;
+        use crate::common::account::order_group::GroupType;
+        use crate::server::db::cmd::create_order::create_order;
+        use crate::server::db::cmd::create_order_group::create_order_group;
+        use crate::server::db::test_db::{
+            test_account, test_db_admin, test_db_trade_conn, test_stock,
+        };
+        use crate::server::network::jwt_wrapper::create_jwt_token;
+
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 10_000 * 100).await;
+
+        /* a bracket whose entry has not filled, the account holds none of the stock */
+        let group_id = create_order_group(&trade_conn, user_id, GroupType::Bracket, 1_000)
+            .await
+            .unwrap();
+        let entry = Order {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            order_type: OrderType::Limit,
+            stock_price: 100.0,
+            stock_amount: 10,
+            status: OrderStatus::Open,
+            group_id,
+            group_role: GroupRole::Entry,
+            ..Order::default()
+        };
+        let take_profit = Order {
+            is_buy: false,
+            stock_price: 110.0,
+            status: OrderStatus::Pending,
+            group_role: GroupRole::Leg,
+            ..entry.clone()
+        };
+        let stop_loss = Order {
+            order_type: OrderType::Stop,
+            stock_price: 0.0,
+            stop_price: 90.0,
+            ..take_profit.clone()
+        };
+        create_order(&trade_conn, user_id, &entry).await.unwrap();
+        let take_profit_id = create_order(&trade_conn, user_id, &take_profit)
+            .await
+            .unwrap();
+        let stop_loss_id = create_order(&trade_conn, user_id, &stop_loss)
+            .await
+            .unwrap();
+
+        let auth_jwt = create_jwt_token(user_id, 4_000_000_000).unwrap();
+        let amend = |order_id, limit_price, stop_price| AmendOrderRequest {
+            auth_jwt: auth_jwt.clone(),
+            order_id,
+            quantity: None,
+            limit_price,
+            stop_price,
+        };
+        let amended = acc_amend_order(&trade_conn, &amend(take_profit_id, Some(115.0), None))
+            .await
+            .unwrap();
+        assert_eq!(
+            (amended.stock_price, amended.status),
+            (115.0, OrderStatus::Pending)
+        );
+        let amended = acc_amend_order(&trade_conn, &amend(stop_loss_id, None, Some(85.0)))
+            .await
+            .unwrap();
+        assert_eq!(
+            (amended.stop_price, amended.status),
+            (85.0, OrderStatus::Pending)
+        );
+
+        let (_, stored) = get_order(&trade_conn, take_profit_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.stock_price, 115.0);
+        let (_, stored) = get_order(&trade_conn, stop_loss_id).await.unwrap().unwrap();
+        assert_eq!(stored.stop_price, 85.0);
+    }
+}
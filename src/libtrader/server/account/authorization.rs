@@ -2,9 +2,7 @@ use data_encoding::HEXUPPER;
 use ring::pbkdf2;
 use std::num::NonZeroU32;
 
-use crate::common::message::message::Message;
-use crate::common::message::message_builder::message_builder;
-use crate::common::message::message_type::MessageType;
+use crate::common::message::request::LoginRequest;
 use crate::common::misc::return_flags::ReturnFlags;
 
 use crate::server::db::cmd::get_user_hash::get_user_hash;
@@ -13,29 +11,11 @@ use crate::server::db::cmd::get_user_salt::get_user_salt;
 
 use crate::server::network::jwt_wrapper::create_jwt_token;
 
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpStream;
-use tokio_rustls::server::TlsStream;
-
 pub async fn acc_auth(
     sql_conn: &tokio_postgres::Client,
-    tls_connection: &mut TlsStream<TcpStream>,
-    message: &Message,
-) -> Result<(), ReturnFlags> {
-    /*
-     * Parse account data.
-     * */
-    /* get json data */
-    let stringified_data = std::str::from_utf8(&message.data).unwrap();
-    let data = json::parse(&stringified_data).unwrap();
-    /* get email, password, and username hashes */
-    let email_hash = HEXUPPER
-        .decode(data["hashed_email"].as_str().unwrap().as_bytes())
-        .unwrap();
-    let password_hash = HEXUPPER
-        .decode(data["hashed_password"].as_str().unwrap().as_bytes())
-        .unwrap();
-    let username = data["username"].as_str().unwrap();
+    login_req: &LoginRequest,
+) -> Result<String, ReturnFlags> {
+    let username = login_req.username.as_str();
 
     /*
      * Get server salts
@@ -43,39 +23,27 @@ pub async fn acc_auth(
     let email_salt = HEXUPPER
         .decode(
             get_user_salt(sql_conn, username, true, true)
-                .await
-                .unwrap()
+                .await?
                 .as_bytes(),
         )
-        .unwrap();
+        .map_err(|_| ReturnFlags::ServerDbUserSaltNotFound)?;
     let password_salt = HEXUPPER
         .decode(
             get_user_salt(sql_conn, username, false, true)
-                .await
-                .unwrap()
+                .await?
                 .as_bytes(),
         )
-        .unwrap();
+        .map_err(|_| ReturnFlags::ServerDbUserSaltNotFound)?;
 
     /*
      * Get server hashes
      * */
     let email_db = HEXUPPER
-        .decode(
-            get_user_hash(sql_conn, username, true)
-                .await
-                .unwrap()
-                .as_bytes(),
-        )
-        .unwrap();
+        .decode(get_user_hash(sql_conn, username, true).await?.as_bytes())
+        .map_err(|_| ReturnFlags::ServerDbUserHashNotFound)?;
     let password_db = HEXUPPER
-        .decode(
-            get_user_hash(sql_conn, username, false)
-                .await
-                .unwrap()
-                .as_bytes(),
-        )
-        .unwrap();
+        .decode(get_user_hash(sql_conn, username, false).await?.as_bytes())
+        .map_err(|_| ReturnFlags::ServerDbUserHashNotFound)?;
 
     /*
      * Verify creds
@@ -84,7 +52,7 @@ pub async fn acc_auth(
         pbkdf2::PBKDF2_HMAC_SHA512,
         NonZeroU32::new(350_000).unwrap(),
         &email_salt,
-        &email_hash,
+        &login_req.hashed_email,
         &email_db,
     );
     match email_ret.is_ok() {
@@ -95,7 +63,7 @@ pub async fn acc_auth(
         pbkdf2::PBKDF2_HMAC_SHA512,
         NonZeroU32::new(500_000).unwrap(),
         &password_salt,
-        &password_hash,
+        &login_req.hashed_password,
         &password_db,
     );
     match pass_ret.is_ok() {
@@ -120,21 +88,5 @@ pub async fn acc_auth(
             .as_secs(),
     )?;
 
-    /*
-     * Send the JWT token
-     * */
-    let message = message_builder(
-        MessageType::ServerReturn,
-        1,
-        1,
-        0,
-        0,
-        jwt_token.as_bytes().to_vec(),
-    );
-    tls_connection
-        .write_all(bincode::serialize(&message).unwrap().as_slice())
-        .await
-        .expect("could not write to client");
-
-    Ok(())
+    Ok(jwt_token)
 }
@@ -0,0 +1,59 @@
+use log::warn;
+
+use crate::common::account::order::{Order, OrderStatus};
+use crate::common::message::request::CancelOrderRequest;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_orders::get_order;
+use crate::server::engine::close_order::close_order;
+use crate::server::engine::engine_conn::connect_trade_conn;
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Cancels an open or pending order of an authorized account.
+///
+/// The order keeps the shares it filled already. Cancelling the entry of a bracket activates
+/// its exits for those shares, or cancels them if it filled none, see ```resolve_group()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use for reads.
+/// cancel_req - The id of the order, with the JWT token of the account owning it.
+///
+/// Returns: the cancelled ```Order``` on success, ```ReturnFlags::ServerOrderNotFound``` if
+/// the account has no order with the id, ```ReturnFlags::ServerOrderNotOpen``` if the order is
+/// filled or closed already, ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let order = acc_cancel_order(&sql_conn, &cancel_req).await?;
+/// ```
+pub async fn acc_cancel_order(
+    sql_conn: &tokio_postgres::Client,
+    cancel_req: &CancelOrderRequest,
+) -> Result<Order, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(cancel_req.auth_jwt.clone()) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_CANCEL_ORDER_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+
+    /* the owner of an order never changes, its status is checked again when closing it */
+    match get_order(sql_conn, cancel_req.order_id).await? {
+        Some((user_id, order)) if user_id == token.user_id => {
+            if order.status.is_final() {
+                return Err(ReturnFlags::ServerOrderNotOpen);
+            }
+        }
+        _ => return Err(ReturnFlags::ServerOrderNotFound),
+    }
+
+    let mut trade_conn = connect_trade_conn().await?;
+    match close_order(&mut trade_conn, cancel_req.order_id, OrderStatus::Cancelled).await? {
+        Some(order) if order.status == OrderStatus::Cancelled => Ok(order),
+        Some(_) => Err(ReturnFlags::ServerOrderNotOpen),
+        None => Err(ReturnFlags::ServerOrderNotFound),
+    }
+}
@@ -1,73 +1,36 @@
 use data_encoding::HEXUPPER;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::common::account::portfolio::Portfolio;
-use crate::common::message::message::Message;
+use crate::common::message::request::RegisterRequest;
 use crate::common::misc::return_flags::ReturnFlags;
 
 use crate::server::account::hash_email::hash_email;
 use crate::server::account::hash_pwd::hash_pwd;
+use crate::server::db::cmd::apply_cash::CashReason;
 use crate::server::ds::account::Account;
+use crate::server::ds::trading_config::trading_config;
 
 pub async fn acc_create(
     sql_conn: &tokio_postgres::Client,
-    message: &Message,
+    register_req: &RegisterRequest,
 ) -> Result<(), ReturnFlags> {
-    /*
-     * Parse account data
-     * */
-    /* get json data */
-    let stringified_data = std::str::from_utf8(&message.data).unwrap().to_string();
-    let data = json::parse(&stringified_data).unwrap();
-    /* get email, password salts and client hashes */
-    let email_hash = HEXUPPER
-        .decode(data["email_hash"].as_str().unwrap().to_string().as_bytes())
-        .unwrap();
-    let email_client_salt = HEXUPPER
-        .decode(
-            data["email_client_salt"]
-                .as_str()
-                .unwrap()
-                .to_string()
-                .as_bytes(),
-        )
-        .unwrap();
-    let password_hash = HEXUPPER
-        .decode(
-            data["password_hash"]
-                .as_str()
-                .unwrap()
-                .to_string()
-                .as_bytes(),
-        )
-        .unwrap();
-    let password_client_salt = HEXUPPER
-        .decode(
-            data["password_client_salt"]
-                .as_str()
-                .unwrap()
-                .to_string()
-                .as_bytes(),
-        )
-        .unwrap();
-
-    /* get username */
-    let username: String = data["username"].as_str().unwrap().to_string();
-
     /* generate account struct */
     let mut account: Account = Account {
-        username: username,
+        username: register_req.username.clone(),
 
         email_hash: "".to_string(),
         server_email_salt: "".to_string(),
-        client_email_salt: HEXUPPER.encode(&email_client_salt),
+        client_email_salt: HEXUPPER.encode(&register_req.email_client_salt),
 
         pass_hash: "".to_string(),
         server_pass_salt: "".to_string(),
-        client_pass_salt: HEXUPPER.encode(&password_client_salt),
+        client_pass_salt: HEXUPPER.encode(&register_req.password_client_salt),
 
         is_pass: true,
         portfolio: Portfolio::default(),
         transactions: Vec::new(),
+        cash_balance: trading_config().starting_cash,
     };
 
     /*
@@ -75,14 +38,14 @@ pub async fn acc_create(
      * */
 
     /* search for an account with same name */
-    for _ in &sql_conn
+    let same_name = sql_conn
         .query(
             "SELECT username FROM accounts_schema.accounts WHERE username LIKE $1",
             &[&account.username],
         )
         .await
-        .unwrap()
-    {
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    if !same_name.is_empty() {
         return Err(ReturnFlags::ServerAccUserExists);
     }
 
@@ -90,24 +53,31 @@ pub async fn acc_create(
      * Hash the email and password.
      * */
     /* hash the email */
-    let email_server_hash = hash_email(&email_hash);
+    let email_server_hash = hash_email(&register_req.email_hash);
     account.email_hash = HEXUPPER.encode(&email_server_hash.0);
     account.server_email_salt = HEXUPPER.encode(&email_server_hash.1);
     /* hash the password */
-    let password_server_hash = hash_pwd(&password_hash);
+    let password_server_hash = hash_pwd(&register_req.password_hash);
     account.pass_hash = HEXUPPER.encode(&password_server_hash.0);
     account.server_pass_salt = HEXUPPER.encode(&password_server_hash.1);
 
     /*
-     * Write the account to the database.
+     * Write the account and its starting cash to the database, in a single statement.
      * */
-    match sql_conn.execute("INSERT INTO accounts_schema.accounts \
-        (username, email_hash, server_email_salt, client_email_salt, pass_hash, server_pass_salt, client_pass_salt)
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+    match sql_conn.execute("WITH account AS (INSERT INTO accounts_schema.accounts \
+        (username, email_hash, server_email_salt, client_email_salt, pass_hash, server_pass_salt, client_pass_salt, cash_balance)
         VALUES \
-        ($1, $2, $3, $4, $5, $6, $7)",
+        ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id, cash_balance) \
+        INSERT INTO accounts_schema.cash_ledger (user_id, amount, reason, stock_symbol, balance, time_epoch) \
+        SELECT id, cash_balance, $9, '', cash_balance, $10 FROM account",
         &[&account.username,
         &account.email_hash, &account.server_email_salt, &account.client_email_salt,
-        &account.pass_hash, &account.server_pass_salt, &account.client_pass_salt]).await {
+        &account.pass_hash, &account.server_pass_salt, &account.client_pass_salt,
+        &account.cash_balance, &CashReason::Deposit.as_str(), &time_epoch]).await {
             Ok(_) => return Ok(()),
             Err(_) => return Err(ReturnFlags::ServerDbWriteFailed),
     }
@@ -0,0 +1,360 @@
+use log::warn;
+use tokio_postgres::GenericClient;
+
+use crate::common::account::fill::Fill;
+use crate::common::account::position::Position;
+use crate::common::account::transaction::{Transaction, TransactionKind};
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::apply_cash::{apply_cash, CashReason};
+use crate::server::db::cmd::create_position::create_position;
+use crate::server::db::cmd::create_transaction::create_transaction;
+use crate::server::db::cmd::get_open_positions::{
+    get_open_positions_for_update, get_order_positions_for_update,
+};
+use crate::server::db::cmd::update_position::update_position;
+use crate::server::ds::trading_config::trading_config;
+
+/// Records the execution of a purchase.
+///
+/// Debits the cost of the shares from the cash balance of the account, then stores the
+/// ```Transaction``` and the open ```Position``` of the purchase. The position remembers the
+/// ```order_id``` of the fill. The fees of the configured ```CostModel``` are charged as
+/// transactions of their own and added to the cost of the position. Market and limit orders
+/// are executed alike.
+/// Must be used within a database transaction, which is rolled back on error.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// user_id - The ID of the account buying.
+/// fill - The execution of the purchase.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerAccInsufficientCash``` if the account
+/// cannot pay for the shares and their fees, ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     execute_buy(&db_transaction, user_id, &fill).await?;
+///     db_transaction.commit().await?;
+/// ```
+pub async fn execute_buy<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    fill: &Fill,
+) -> Result<(), ReturnFlags> {
+    let fees = trading_config().cost_model.fees(fill.quantity, fill.price);
+    let (transaction, position) = purchase_records(fill, &fees);
+    apply_cash(
+        sql_conn,
+        user_id,
+        -transaction.shares_cost,
+        CashReason::Buy,
+        &fill.stock_symbol,
+        fill.time_epoch,
+    )
+    .await?;
+    create_transaction(sql_conn, user_id, &transaction).await?;
+    charge_fees(sql_conn, user_id, fill, true, &fees).await?;
+    create_position(sql_conn, user_id, fill.order_id, position).await?;
+    Ok(())
+}
+
+/// Records the execution of a sale.
+///
+/// The shares are taken from the open positions of the account first in first out, closing
+/// them fully or partially. When ```entry_id``` is set, only the positions opened by that
+/// order are closed, as for the exits of a bracket. The proceeds are credited to the cash
+/// balance of the account and the sale is stored as a ```Transaction```. The fees of the
+/// configured ```CostModel``` are charged as transactions of their own and taken from the
+/// proceeds of the positions. Market and limit orders are executed alike.
+/// Must be used within a database transaction, which is rolled back on error.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// user_id - The ID of the account selling.
+/// fill - The execution of the sale, its ```realized_pnl``` is set here.
+/// entry_id - The id of the order whose positions are closed, ```None``` for any position.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerSellAssetOversold``` if the account holds
+/// fewer shares than sold, ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     execute_sell(&db_transaction, user_id, &mut fill, None).await?;
+///     db_transaction.commit().await?;
+/// ```
+pub async fn execute_sell<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    fill: &mut Fill,
+    entry_id: Option<i64>,
+) -> Result<(), ReturnFlags> {
+    let fees = trading_config().cost_model.fees(fill.quantity, fill.price);
+    let lots = match entry_id {
+        Some(entry_id) => get_order_positions_for_update(sql_conn, user_id, entry_id).await?,
+        None => get_open_positions_for_update(sql_conn, user_id, &fill.stock_symbol).await?,
+    };
+    let (closed_lots, realized_pnl) = close_lots_fifo(
+        lots,
+        fill.quantity,
+        fill.price,
+        fees.iter().map(|(_, fee)| fee).sum(),
+        fill.time_epoch,
+    )?;
+    for (position_id, position) in &closed_lots {
+        update_position(sql_conn, *position_id, position).await?;
+    }
+    fill.realized_pnl = realized_pnl;
+
+    let transaction = Transaction {
+        stock_symbol: fill.stock_symbol.clone(),
+        shares_size: fill.quantity,
+        shares_cost: (fill.price * fill.quantity as f64 * 100.0).round() as i64,
+        is_buy: false,
+        kind: TransactionKind::Trade,
+    };
+    apply_cash(
+        sql_conn,
+        user_id,
+        transaction.shares_cost,
+        CashReason::Sell,
+        &fill.stock_symbol,
+        fill.time_epoch,
+    )
+    .await?;
+    create_transaction(sql_conn, user_id, &transaction).await?;
+    charge_fees(sql_conn, user_id, fill, false, &fees).await?;
+    Ok(())
+}
+
+/// Debits the fees of a fill from the cash balance of an account, storing every fee as a
+/// ```Transaction```.
+async fn charge_fees<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    fill: &Fill,
+    is_buy: bool,
+    fees: &[(TransactionKind, i64)],
+) -> Result<(), ReturnFlags> {
+    for (kind, fee) in fees {
+        apply_cash(
+            sql_conn,
+            user_id,
+            -fee,
+            CashReason::Fee,
+            &fill.stock_symbol,
+            fill.time_epoch,
+        )
+        .await?;
+        let transaction = Transaction {
+            stock_symbol: fill.stock_symbol.clone(),
+            shares_size: fill.quantity,
+            shares_cost: *fee,
+            is_buy,
+            kind: *kind,
+        };
+        create_transaction(sql_conn, user_id, &transaction).await?;
+    }
+    Ok(())
+}
+
+/// Returns the ```Transaction``` and the open ```Position``` recording a purchase, the cost of
+/// the position including the ```fees``` in cents.
+fn purchase_records(fill: &Fill, fees: &[(TransactionKind, i64)]) -> (Transaction, Position) {
+    let cost = fill.price * fill.quantity as f64;
+    let fees: i64 = fees.iter().map(|(_, fee)| fee).sum();
+    let transaction = Transaction {
+        stock_symbol: fill.stock_symbol.clone(),
+        shares_size: fill.quantity,
+        shares_cost: (cost * 100.0).round() as i64,
+        is_buy: true,
+        kind: TransactionKind::Trade,
+    };
+    let position = Position {
+        is_buy: true,
+        stock_symbol: fill.stock_symbol.clone(),
+        stock_open_amount: fill.quantity,
+        stock_open_price: fill.price,
+        stock_open_cost: cost + fees as f64 / 100.0,
+        open_epoch: fill.time_epoch,
+        is_open: true,
+        ..Position::default()
+    };
+    (transaction, position)
+}
+
+/// Closes ```quantity``` shares of open lots, oldest first.
+///
+/// A lot is partially closed by growing its ```stock_close_amount```, its
+/// ```stock_close_price``` being the average price of the shares closed so far. It stays open
+/// until every share is closed. The fees of the sale are shared between the lots by the
+/// number of shares closed, and taken from their ```stock_close_cost```. The realized profit
+/// is made against the ```stock_open_cost``` of the lots, fees of the purchase included.
+///
+/// Arguments:
+/// lots - The open lots and the ids of their rows, oldest first.
+/// quantity - The number of shares to close.
+/// price - The price the shares are sold at.
+/// fees - The fees of the sale, in cents.
+/// time_epoch - The unix epoch of the sale.
+///
+/// Returns: the lots that were closed, fully or partially, and the realized profit on
+/// success, ```ReturnFlags::ServerSellAssetOversold``` if the lots hold fewer shares than
+/// ```quantity```.
+fn close_lots_fifo(
+    lots: Vec<(i64, Position)>,
+    quantity: i64,
+    price: f64,
+    fees: i64,
+    time_epoch: i64,
+) -> Result<(Vec<(i64, Position)>, f64), ReturnFlags> {
+    let held: i64 = lots
+        .iter()
+        .map(|(_, lot)| lot.stock_open_amount - lot.stock_close_amount)
+        .sum();
+    if held < quantity {
+        warn!("ACC_SELL_ASSET_OVERSOLD: {} < {}", held, quantity);
+        return Err(ReturnFlags::ServerSellAssetOversold);
+    }
+
+    let mut left = quantity;
+    let mut realized_pnl = 0.0;
+    let mut closed_lots = Vec::new();
+    for (position_id, mut lot) in lots {
+        if left == 0 {
+            break;
+        }
+        let closed = left.min(lot.stock_open_amount - lot.stock_close_amount);
+        if closed <= 0 {
+            continue;
+        }
+        left -= closed;
+
+        let close_amount = lot.stock_close_amount + closed;
+        let proceeds =
+            price * closed as f64 - fees as f64 / 100.0 * closed as f64 / quantity as f64;
+        lot.stock_close_price = (lot.stock_close_price * lot.stock_close_amount as f64
+            + price * closed as f64)
+            / close_amount as f64;
+        lot.stock_close_cost += proceeds;
+        lot.stock_close_amount = close_amount;
+        lot.close_epoch = time_epoch;
+        lot.is_open = close_amount < lot.stock_open_amount;
+        realized_pnl +=
+            proceeds - lot.stock_open_cost * closed as f64 / lot.stock_open_amount as f64;
+
+        closed_lots.push((position_id, lot));
+    }
+
+    Ok((closed_lots, realized_pnl))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lot(amount: i64, price: f64, open_epoch: i64) -> Position {
+        Position {
+            is_buy: true,
+            stock_symbol: "AAPL".to_string(),
+            stock_open_amount: amount,
+            stock_open_price: price,
+            stock_open_cost: price * amount as f64,
+            open_epoch,
+            is_open: true,
+            ..Position::default()
+        }
+    }
+
+    #[test]
+    fn test_purchase_records() {
+        let fill = Fill {
+            stock_symbol: "AAPL".to_string(),
+            quantity: 3,
+            price: 12.34,
+            time_epoch: 1_000,
+            realized_pnl: 0.0,
+            order_id: 0,
+        };
+        let (transaction, position) = purchase_records(&fill, &[]);
+        assert_eq!(transaction.shares_size, 3);
+        assert_eq!(transaction.shares_cost, 3702);
+        assert!(transaction.is_buy);
+        assert_eq!(position.stock_open_amount, 3);
+        assert!((position.stock_open_cost - 37.02).abs() < 1e-9);
+        assert!(position.is_open && position.is_buy);
+
+        /* fees are paid apart, but make the cost of the position */
+        let fees = [
+            (TransactionKind::FlatFee, 100),
+            (TransactionKind::Commission, 4),
+        ];
+        let (transaction, position) = purchase_records(&fill, &fees);
+        assert_eq!(transaction.shares_cost, 3702);
+        assert_eq!(transaction.kind, TransactionKind::Trade);
+        assert!((position.stock_open_cost - 38.06).abs() < 1e-9);
+        assert_eq!(position.stock_open_price, 12.34);
+    }
+
+    #[test]
+    fn test_close_lots_fifo() {
+        let lots = vec![
+            (1, lot(10, 100.0, 1)),
+            (2, lot(10, 110.0, 2)),
+            (3, lot(5, 90.0, 3)),
+        ];
+
+        /* the oldest lot is closed, the next one partially */
+        let (closed, pnl) = close_lots_fifo(lots, 15, 120.0, 0, 50).unwrap();
+        assert_eq!(closed.len(), 2);
+        assert_eq!(closed[0].0, 1);
+        assert!(!closed[0].1.is_open);
+        assert_eq!(closed[0].1.stock_close_amount, 10);
+        assert_eq!(closed[0].1.stock_close_cost, 1200.0);
+        assert_eq!(closed[0].1.close_epoch, 50);
+        assert_eq!(closed[1].0, 2);
+        assert!(closed[1].1.is_open);
+        assert_eq!(closed[1].1.stock_close_amount, 5);
+        assert_eq!(pnl, 10.0 * 20.0 + 5.0 * 10.0);
+
+        /* closing the rest of a partially closed lot averages its close price */
+        let (closed, pnl) = close_lots_fifo(vec![closed[1].clone()], 5, 100.0, 0, 60).unwrap();
+        assert!(!closed[0].1.is_open);
+        assert_eq!(closed[0].1.stock_close_amount, 10);
+        assert_eq!(closed[0].1.stock_close_price, 110.0);
+        assert_eq!(closed[0].1.stock_close_cost, 1100.0);
+        assert_eq!(pnl, -50.0);
+    }
+
+    #[test]
+    fn test_close_lots_fifo_fees() {
+        /* the lots were bought for 1010 and 505, fees included */
+        let mut first = lot(10, 100.0, 1);
+        first.stock_open_cost = 1010.0;
+        let mut second = lot(5, 100.0, 2);
+        second.stock_open_cost = 505.0;
+
+        /* 30 of fees on 15 shares, 2 per share */
+        let lots = vec![(1, first), (2, second)];
+        let (closed, pnl) = close_lots_fifo(lots, 15, 120.0, 3000, 50).unwrap();
+        assert!((closed[0].1.stock_close_cost - 1180.0).abs() < 1e-9);
+        assert!((closed[1].1.stock_close_cost - 590.0).abs() < 1e-9);
+        assert_eq!(closed[0].1.stock_close_price, 120.0);
+        assert!((pnl - (1770.0 - 1515.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_close_lots_fifo_oversold() {
+        let lots = vec![(1, lot(10, 100.0, 1))];
+        assert_eq!(
+            close_lots_fifo(lots, 11, 120.0, 0, 50),
+            Err(ReturnFlags::ServerSellAssetOversold)
+        );
+        assert_eq!(
+            close_lots_fifo(Vec::new(), 1, 120.0, 0, 50),
+            Err(ReturnFlags::ServerSellAssetOversold)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use log::warn;
+use tokio_postgres::GenericClient;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::apply_cash::{apply_cash, CashReason};
+
+/// Pays a cash dividend to every account holding shares of a stock.
+///
+/// Every open lot of the stock is credited ```cents_per_share``` for each of its shares not
+/// sold yet, with one ledger entry per lot. The lots stay locked until the end of the database
+/// transaction, so sales of the stock wait for the payment, and the shares paid are the shares
+/// held when it is made. Dividends are paid by ```run_dividend_payments()``` once they are
+/// due, their ```pay_epoch``` standing for the record date.
+/// Should be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// symbol - The symbol of the stock paying the dividend.
+/// cents_per_share - The dividend of one share, in cents.
+/// time_epoch - The unix epoch of the payment.
+///
+/// Returns: the number of lots paid on success, ```ReturnFlags::ServerPayDividendInvAmount```
+/// if ```cents_per_share``` is not positive, ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let paid = pay_dividend(&db_transaction, "AAPL", 23, time_epoch).await?;
+/// ```
+pub async fn pay_dividend<C: GenericClient>(
+    sql_conn: &C,
+    symbol: &str,
+    cents_per_share: i64,
+    time_epoch: i64,
+) -> Result<usize, ReturnFlags> {
+    if cents_per_share <= 0 {
+        warn!("PAY_DIVIDEND_INV_AMOUNT: {} {}", symbol, cents_per_share);
+        return Err(ReturnFlags::ServerPayDividendInvAmount);
+    }
+
+    let lots = sql_conn
+        .query(
+            "SELECT user_id, stock_open_amount - stock_close_amount
+                FROM portfolio_schema.positions
+                WHERE stock_symbol = $1 AND is_open AND is_buy
+                ORDER BY open_epoch, id
+                FOR UPDATE",
+            &[&symbol],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+
+    for lot in &lots {
+        let user_id: i64 = lot
+            .try_get(0)
+            .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+        let shares: i64 = lot
+            .try_get(1)
+            .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+        let amount = shares.checked_mul(cents_per_share).ok_or_else(|| {
+            warn!("PAY_DIVIDEND_OVERFLOW: {} {}", symbol, user_id);
+            ReturnFlags::ServerDbWriteFailed
+        })?;
+        apply_cash(
+            sql_conn,
+            user_id,
+            amount,
+            CashReason::Dividend,
+            symbol,
+            time_epoch,
+        )
+        .await?;
+    }
+
+    Ok(lots.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::account::position::Position;
+    use crate::server::db::cmd::create_position::create_position;
+    use crate::server::db::test_db::{test_account, test_db_admin, test_db_trade_conn, test_stock};
+
+    #[tokio::test]
+    async fn test_pay_dividend_per_lot() {
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let mut trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 0).await;
+
+        /* a partially sold lot, a full lot, and a sold lot */
+        let lot = Position {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            stock_open_amount: 10,
+            stock_open_price: 100.0,
+            stock_close_amount: 4,
+            is_open: true,
+            ..Position::default()
+        };
+        for position in [
+            lot.clone(),
+            Position {
+                stock_open_amount: 5,
+                stock_close_amount: 0,
+                ..lot.clone()
+            },
+            Position {
+                stock_close_amount: 10,
+                is_open: false,
+                ..lot
+            },
+        ]
+        .iter()
+        .cloned()
+        {
+            create_position(&trade_conn, user_id, 0, position)
+                .await
+                .unwrap();
+        }
+
+        let db_transaction = trade_conn.transaction().await.unwrap();
+        assert_eq!(
+            pay_dividend(&db_transaction, &symbol, 0, 1_000).await,
+            Err(ReturnFlags::ServerPayDividendInvAmount)
+        );
+        assert_eq!(
+            pay_dividend(&db_transaction, &symbol, 25, 1_000).await,
+            Ok(2)
+        );
+        db_transaction.commit().await.unwrap();
+
+        let ledger: Vec<(i64, i64)> = admin_conn
+            .query(
+                "SELECT amount, balance FROM accounts_schema.cash_ledger
+                    WHERE user_id = $1 AND reason = 'dividend' AND stock_symbol = $2
+                    ORDER BY id",
+                &[&user_id, &symbol],
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+        assert_eq!(ledger, vec![(150, 150), (125, 275)]);
+    }
+
+    #[tokio::test]
+    async fn test_pay_dividend_after_sale() {
+        use crate::common::account::fill::Fill;
+        use crate::server::account::execution::execute_sell;
+
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let mut trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 0).await;
+        let lot = Position {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            stock_open_amount: 10,
+            stock_open_price: 100.0,
+            is_open: true,
+            ..Position::default()
+        };
+        create_position(&trade_conn, user_id, 0, lot).await.unwrap();
+
+        /* shares sold before the payment are not paid */
+        let db_transaction = trade_conn.transaction().await.unwrap();
+        let mut fill = Fill {
+            stock_symbol: symbol.clone(),
+            quantity: 4,
+            price: 100.0,
+            time_epoch: 1_000,
+            ..Fill::default()
+        };
+        execute_sell(&db_transaction, user_id, &mut fill, None)
+            .await
+            .unwrap();
+        db_transaction.commit().await.unwrap();
+
+        let db_transaction = trade_conn.transaction().await.unwrap();
+        assert_eq!(
+            pay_dividend(&db_transaction, &symbol, 10, 1_001).await,
+            Ok(1)
+        );
+        db_transaction.commit().await.unwrap();
+
+        let amounts: Vec<i64> = admin_conn
+            .query(
+                "SELECT amount FROM accounts_schema.cash_ledger
+                    WHERE user_id = $1 AND reason = 'dividend' AND stock_symbol = $2",
+                &[&user_id, &symbol],
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        assert_eq!(amounts, vec![60]);
+    }
+}
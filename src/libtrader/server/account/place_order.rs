@@ -0,0 +1,423 @@
+use log::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_postgres::GenericClient;
+
+use crate::common::account::order::{Order, OrderStatus, OrderType, TimeInForce};
+use crate::common::generic::stock_val::StockVal;
+use crate::common::message::request::PlaceOrderRequest;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::create_order::create_order;
+use crate::server::db::cmd::get_cash_balance::get_cash_balance;
+use crate::server::db::cmd::get_company::get_company_from_db;
+use crate::server::db::cmd::get_open_positions::get_open_positions_for_update;
+use crate::server::db::cmd::get_stock::get_stock_from_db_latest;
+use crate::server::ds::trading_config::trading_config;
+use crate::server::engine::close_order::close_order;
+use crate::server::engine::engine_conn::{
+    begin_transaction, commit_transaction, connect_trade_conn,
+};
+use crate::server::engine::fill_order::fill_order;
+use crate::server::engine::match_order::match_order;
+use crate::server::engine::trigger_order::trigger_order;
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Places an order.
+///
+/// Orders the account could not pay or deliver for are refused. The order is evaluated
+/// against the latest ```StockVal``` of the stock, see ```trigger_order()```, stored open,
+/// then filled at once as far as the quote allows. The rest of the order is triggered and
+/// filled by the matching engine as new quotes come, see ```run_matching_engine()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use for reads.
+/// order_req - The order, with the JWT token of the account placing it.
+///
+/// Returns: the ```Order``` as stored on success, ```ReturnFlags::ServerAccInsufficientCash```
+/// or ```ReturnFlags::ServerSellAssetOversold``` if the account cannot cover the order,
+/// ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let order = acc_place_order(&sql_conn, &order_req).await?;
+///     info!("order {} filled {}/{}", order.id, order.stock_filled, order.stock_amount);
+/// ```
+pub async fn acc_place_order(
+    sql_conn: &tokio_postgres::Client,
+    order_req: &PlaceOrderRequest,
+) -> Result<Order, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(order_req.auth_jwt.clone()) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_PLACE_ORDER_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+    let terms = Order {
+        is_buy: order_req.is_buy,
+        stock_symbol: order_req.symbol.clone(),
+        order_type: order_req.order_type,
+        stock_price: order_req.limit_price,
+        stock_amount: order_req.quantity,
+        stop_price: order_req.stop_price,
+        trail_amount: order_req.trail_amount,
+        time_in_force: order_req.time_in_force,
+        ..Order::default()
+    };
+    let mut order = order_terms(&terms, time_epoch)?;
+
+    /* only listed companies can be traded */
+    get_company_from_db(sql_conn, &order.stock_symbol).await?;
+
+    /* a stop already crossed is triggered at once, a trailing stop starts from the quote */
+    let quote = get_stock_from_db_latest(sql_conn, &order.stock_symbol)
+        .await
+        .ok();
+    if let Some(quote) = &quote {
+        trigger_order(&mut order, quote);
+    }
+
+    let mut trade_conn = connect_trade_conn().await?;
+    let db_transaction = begin_transaction(&mut trade_conn).await?;
+    check_coverage(&db_transaction, token.user_id, &order, quote.as_ref()).await?;
+    order.id = create_order(&db_transaction, token.user_id, &order).await?;
+    commit_transaction(db_transaction).await?;
+
+    Ok(fill_placed(&mut trade_conn, order, quote.as_ref(), time_epoch).await)
+}
+
+/// Builds a new order out of the terms asked by a client, checking them.
+///
+/// Only the side, symbol, type, amount, time in force and the prices used by the type are
+/// kept, the order starts open. Day orders expire at the close of the current exchange
+/// session, see ```TradingConfig::session_close_after()```. Stop orders cannot be immediate,
+/// they would never wait for their trigger.
+///
+/// Arguments:
+/// terms - The terms of the order.
+/// time_epoch - The unix epoch at which the order is placed.
+///
+/// Returns: the ```Order```, not yet stored, on success,
+/// ```ReturnFlags::ServerPlaceOrderInvMsg``` if a price, the amount or the time in force is
+/// invalid.
+pub fn order_terms(terms: &Order, time_epoch: i64) -> Result<Order, ReturnFlags> {
+    let valid = |price: f64| price.is_finite() && price > 0.0;
+    let order_type = terms.order_type;
+    let uses_stop = matches!(order_type, OrderType::Stop | OrderType::StopLimit);
+    let uses_trail = order_type == OrderType::TrailingStop;
+
+    if terms.stock_amount <= 0
+        || (order_type.has_limit() && !valid(terms.stock_price))
+        || (uses_stop && !valid(terms.stop_price))
+        || (uses_trail && !valid(terms.trail_amount))
+        || (order_type.is_stop() && terms.time_in_force.is_immediate())
+    {
+        return Err(ReturnFlags::ServerPlaceOrderInvMsg);
+    }
+
+    let price_if = |used: bool, price: f64| if used { price } else { 0.0 };
+    Ok(Order {
+        is_buy: terms.is_buy,
+        stock_symbol: terms.stock_symbol.clone(),
+        order_type,
+        stock_price: price_if(order_type.has_limit(), terms.stock_price),
+        stock_amount: terms.stock_amount,
+        created_epoch: time_epoch,
+        stop_price: price_if(uses_stop, terms.stop_price),
+        trail_amount: price_if(uses_trail, terms.trail_amount),
+        time_in_force: terms.time_in_force,
+        expire_epoch: match terms.time_in_force {
+            TimeInForce::Day => trading_config().session_close_after(time_epoch),
+            _ => 0,
+        },
+        ..Order::default()
+    })
+}
+
+/// Checks that an account can cover a new order.
+///
+/// A purchase must be paid at its limit, or at the worst of its stop and the latest ask, fees
+/// included. A sale must not exceed the shares held, which stay locked until the end of the
+/// database transaction, so concurrent orders of the account are checked one after the other.
+/// Open orders reserve neither cash nor shares, fills check again as the balance or the
+/// positions may change until then.
+/// Should be used within the database transaction storing the order, see
+/// ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - The database transaction to use.
+/// user_id - The ID of the account placing the order.
+/// order - The new order.
+/// quote - The latest value of the stock, if any.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerAccInsufficientCash``` or
+/// ```ReturnFlags::ServerSellAssetOversold``` if the account cannot cover the order,
+/// ```ReturnFlags``` on other errors.
+pub async fn check_coverage<C: GenericClient>(
+    trade_conn: &C,
+    user_id: i64,
+    order: &Order,
+    quote: Option<&StockVal>,
+) -> Result<(), ReturnFlags> {
+    if order.is_buy {
+        let price = if order.order_type.has_limit() {
+            order.stock_price
+        } else {
+            let ask = quote.map_or(0.0, |quote| quote.ask_price);
+            order.stop_price.max(ask)
+        };
+        let fees: i64 = trading_config()
+            .cost_model
+            .fees(order.stock_amount, price)
+            .iter()
+            .map(|(_, fee)| fee)
+            .sum();
+        let cost = (price * order.stock_amount as f64 * 100.0).round() as i64 + fees;
+        if get_cash_balance(trade_conn, user_id).await? < cost {
+            return Err(ReturnFlags::ServerAccInsufficientCash);
+        }
+    } else {
+        let held: i64 = get_open_positions_for_update(trade_conn, user_id, &order.stock_symbol)
+            .await?
+            .iter()
+            .map(|(_, lot)| lot.stock_open_amount - lot.stock_close_amount)
+            .sum();
+        if held < order.stock_amount {
+            return Err(ReturnFlags::ServerSellAssetOversold);
+        }
+    }
+    Ok(())
+}
+
+/// Fills a newly stored order against the latest quote, as far as the quote allows.
+///
+/// The order is placed already, failing to fill it now only leaves it to the matching
+/// engine. Immediate orders never rest on the server: what an IOC order could not fill is
+/// cancelled, a FOK order is only filled if the quote fills it completely and is rejected
+/// otherwise.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own.
+/// order - The stored order.
+/// quote - The latest value of the stock, if any.
+/// time_epoch - The unix epoch of the fill.
+///
+/// Returns: the order as stored after the fill.
+pub async fn fill_placed(
+    trade_conn: &mut tokio_postgres::Client,
+    order: Order,
+    quote: Option<&StockVal>,
+    time_epoch: i64,
+) -> Order {
+    let matched = quote.and_then(|quote| {
+        match_order(&order, quote, quote.volume).map(|(quantity, _)| (quote, quantity))
+    });
+    let order = match matched {
+        Some((quote, quantity))
+            if order.time_in_force != TimeInForce::Fok
+                || quantity == order.stock_amount - order.stock_filled =>
+        {
+            match fill_order(trade_conn, order.id, quote, quote.volume, time_epoch).await {
+                Ok((order, _)) => order,
+                Err(err) => {
+                    warn!("ACC_PLACE_ORDER_FILL_FAILED: {} {}", order.id, err);
+                    order
+                }
+            }
+        }
+        _ => order,
+    };
+
+    if order.status != OrderStatus::Open || !order.time_in_force.is_immediate() {
+        return order;
+    }
+    let status = match order.time_in_force {
+        TimeInForce::Fok => OrderStatus::Rejected,
+        _ => OrderStatus::Cancelled,
+    };
+    match close_order(trade_conn, order.id, status).await {
+        Ok(Some(closed)) => closed,
+        Ok(None) => order,
+        Err(err) => {
+            warn!("ACC_PLACE_ORDER_CLOSE_FAILED: {} {}", order.id, err);
+            order
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_order_terms() {
+        let terms = Order {
+            id: 7,
+            stock_symbol: "AAPL".to_string(),
+            order_type: OrderType::Stop,
+            stock_price: 99.0,
+            stock_amount: 10,
+            stock_filled: 10,
+            stop_price: 95.0,
+            trail_amount: 2.0,
+            is_triggered: true,
+            ..Order::default()
+        };
+        let order = order_terms(&terms, 1_000).unwrap();
+        assert_eq!(order.order_type, OrderType::Stop);
+        assert_eq!(order.stop_price, 95.0);
+        assert_eq!((order.stock_price, order.trail_amount), (0.0, 0.0));
+        assert_eq!((order.stock_amount, order.created_epoch), (10, 1_000));
+        assert_eq!((order.id, order.stock_filled), (0, 0));
+        assert!(!order.is_triggered);
+
+        /* every type needs the prices it uses */
+        for (order_type, stock_price, stop_price, trail_amount) in [
+            (OrderType::Limit, 0.0, 95.0, 2.0),
+            (OrderType::StopLimit, 99.0, f64::NAN, 0.0),
+            (OrderType::TrailingStop, 99.0, 95.0, -1.0),
+        ] {
+            let terms = Order {
+                order_type,
+                stock_price,
+                stop_price,
+                trail_amount,
+                ..terms.clone()
+            };
+            assert_eq!(
+                order_terms(&terms, 1_000),
+                Err(ReturnFlags::ServerPlaceOrderInvMsg)
+            );
+        }
+
+        let terms = Order {
+            time_in_force: TimeInForce::Ioc,
+            ..terms
+        };
+        assert_eq!(
+            order_terms(&terms, 1_000),
+            Err(ReturnFlags::ServerPlaceOrderInvMsg)
+        );
+
+        let terms = Order {
+            stock_amount: 0,
+            ..terms
+        };
+        assert_eq!(
+            order_terms(&terms, 1_000),
+            Err(ReturnFlags::ServerPlaceOrderInvMsg)
+        );
+    }
+
+    #[test]
+    fn test_order_terms_time_in_force() {
+        let terms = Order {
+            stock_symbol: "AAPL".to_string(),
+            stock_price: 99.0,
+            stock_amount: 10,
+            expire_epoch: 5,
+            ..Order::default()
+        };
+        assert_eq!(order_terms(&terms, 1_000).unwrap().expire_epoch, 0);
+
+        let terms = Order {
+            time_in_force: TimeInForce::Day,
+            ..terms
+        };
+        let order = order_terms(&terms, 1_000).unwrap();
+        assert_eq!(order.time_in_force, TimeInForce::Day);
+        assert_eq!(
+            order.expire_epoch,
+            trading_config().session_close_after(1_000)
+        );
+
+        let terms = Order {
+            time_in_force: TimeInForce::Fok,
+            ..terms
+        };
+        assert_eq!(order_terms(&terms, 1_000).unwrap().expire_epoch, 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_coverage() {
+        use crate::common::account::position::Position;
+        use crate::server::db::cmd::create_position::create_position;
+        use crate::server::db::test_db::{
+            test_account, test_db_admin, test_db_trade_conn, test_stock,
+        };
+
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let mut trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 1_000 * 100).await;
+        let position = Position {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            stock_open_amount: 5,
+            stock_open_price: 100.0,
+            is_open: true,
+            ..Position::default()
+        };
+        create_position(&trade_conn, user_id, 0, position)
+            .await
+            .unwrap();
+
+        let sell = Order {
+            stock_symbol: symbol.clone(),
+            stock_price: 100.0,
+            stock_amount: 5,
+            ..Order::default()
+        };
+        let buy = Order {
+            is_buy: true,
+            stock_amount: 10,
+            ..sell.clone()
+        };
+        let db_transaction = trade_conn.transaction().await.unwrap();
+        assert_eq!(
+            check_coverage(&db_transaction, user_id, &sell, None).await,
+            Ok(())
+        );
+        assert_eq!(
+            check_coverage(&db_transaction, user_id, &buy, None).await,
+            Ok(())
+        );
+        assert_eq!(
+            check_coverage(
+                &db_transaction,
+                user_id,
+                &Order {
+                    stock_amount: 6,
+                    ..sell
+                },
+                None
+            )
+            .await,
+            Err(ReturnFlags::ServerSellAssetOversold)
+        );
+        assert_eq!(
+            check_coverage(
+                &db_transaction,
+                user_id,
+                &Order {
+                    stock_amount: 11,
+                    ..buy
+                },
+                None
+            )
+            .await,
+            Err(ReturnFlags::ServerAccInsufficientCash)
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use log::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::common::account::order::{GroupRole, Order, OrderStatus, OrderType};
+use crate::common::account::order_group::{GroupType, OrderGroup};
+use crate::common::message::request::PlaceOrderGroupRequest;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::account::place_order::{check_coverage, fill_placed, order_terms};
+use crate::server::db::cmd::create_order::create_order;
+use crate::server::db::cmd::create_order_group::create_order_group;
+use crate::server::db::cmd::get_company::get_company_from_db;
+use crate::server::db::cmd::get_orders::get_group_orders;
+use crate::server::db::cmd::get_stock::get_stock_from_db_latest;
+use crate::server::engine::engine_conn::{
+    begin_transaction, commit_transaction, connect_trade_conn,
+};
+use crate::server::engine::trigger_order::trigger_order;
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Places linked orders.
+///
+/// The group and its orders are stored in a single database transaction, so the link
+/// between them survives restarts. The orders waiting for nothing are then placed like
+/// single orders, see ```acc_place_order()```. The matching engine applies the rules of the
+/// group as the orders fill, see ```resolve_group()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use for reads.
+/// group_req - The group, with the JWT token of the account placing it.
+///
+/// Returns: the ```OrderGroup``` as stored on success,
+/// ```ReturnFlags::ServerPlaceOrderGroupInvMsg``` if the orders do not form a valid group,
+/// ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let group = acc_place_order_group(&sql_conn, &group_req).await?;
+///     info!("group {} of {} orders", group.id, group.orders.len());
+/// ```
+pub async fn acc_place_order_group(
+    sql_conn: &tokio_postgres::Client,
+    group_req: &PlaceOrderGroupRequest,
+) -> Result<OrderGroup, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(group_req.auth_jwt.clone()) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_PLACE_ORDER_GROUP_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+    let mut orders = group_orders(group_req.group_type, &group_req.orders, time_epoch)?;
+
+    /* only listed companies can be traded, every order of a group trades the same stock */
+    get_company_from_db(sql_conn, &orders[0].stock_symbol).await?;
+    let quote = get_stock_from_db_latest(sql_conn, &orders[0].stock_symbol)
+        .await
+        .ok();
+    if let Some(quote) = &quote {
+        for order in &mut orders {
+            trigger_order(order, quote);
+        }
+    }
+
+    let mut trade_conn = connect_trade_conn().await?;
+    let db_transaction = begin_transaction(&mut trade_conn).await?;
+    for order in orders
+        .iter()
+        .filter(|order| order.status == OrderStatus::Open)
+    {
+        check_coverage(&db_transaction, token.user_id, order, quote.as_ref()).await?;
+    }
+    let group_id = create_order_group(
+        &db_transaction,
+        token.user_id,
+        group_req.group_type,
+        time_epoch,
+    )
+    .await?;
+    for order in &mut orders {
+        order.group_id = group_id;
+        order.id = create_order(&db_transaction, token.user_id, order).await?;
+    }
+    commit_transaction(db_transaction).await?;
+
+    for order in orders {
+        if order.status == OrderStatus::Open {
+            fill_placed(&mut trade_conn, order, quote.as_ref(), time_epoch).await;
+        }
+    }
+
+    /* fills change the other orders of the group, they are read again */
+    Ok(OrderGroup {
+        id: group_id,
+        group_type: group_req.group_type,
+        orders: get_group_orders(&trade_conn, group_id).await?,
+    })
+}
+
+/// Builds the orders of a new group out of the terms asked by a client, checking them.
+///
+/// An OCO group takes two legs on the same stock. A bracket takes a buy entry, a limit sell
+/// taking the profit and a stop sell limiting the loss, on the same stock. The exits of a
+/// bracket wait for the entry and sell the shares it buys, they cannot be immediate.
+///
+/// Arguments:
+/// group_type - The kind of the group.
+/// terms - The terms of the orders, the entry of a bracket first.
+/// time_epoch - The unix epoch at which the group is placed.
+///
+/// Returns: the orders, not yet stored, on success, ```ReturnFlags::ServerPlaceOrderInvMsg```
+/// if the terms of an order are invalid, ```ReturnFlags::ServerPlaceOrderGroupInvMsg``` if
+/// the orders do not form a valid group.
+fn group_orders(
+    group_type: GroupType,
+    terms: &[Order],
+    time_epoch: i64,
+) -> Result<Vec<Order>, ReturnFlags> {
+    let mut orders = terms
+        .iter()
+        .map(|terms| order_terms(terms, time_epoch))
+        .collect::<Result<Vec<_>, _>>()?;
+    let same_stock = orders
+        .iter()
+        .all(|order| order.stock_symbol == orders[0].stock_symbol);
+
+    match (group_type, orders.as_mut_slice()) {
+        (GroupType::Oco, [first, second]) if same_stock => {
+            first.group_role = GroupRole::Leg;
+            second.group_role = GroupRole::Leg;
+        }
+        (GroupType::Bracket, [entry, take_profit, stop_loss])
+            if same_stock
+                && entry.is_buy
+                && !take_profit.is_buy
+                && take_profit.order_type == OrderType::Limit
+                && !stop_loss.is_buy
+                && stop_loss.order_type.is_stop()
+                && !take_profit.time_in_force.is_immediate() =>
+        {
+            entry.group_role = GroupRole::Entry;
+            for exit in [take_profit, stop_loss] {
+                exit.group_role = GroupRole::Leg;
+                exit.status = OrderStatus::Pending;
+                exit.stock_amount = entry.stock_amount;
+            }
+        }
+        _ => return Err(ReturnFlags::ServerPlaceOrderGroupInvMsg),
+    }
+    Ok(orders)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn terms(is_buy: bool, order_type: OrderType, stock_amount: i64) -> Order {
+        Order {
+            is_buy,
+            stock_symbol: "AAPL".to_string(),
+            order_type,
+            stock_price: 100.0,
+            stock_amount,
+            stop_price: 90.0,
+            ..Order::default()
+        }
+    }
+
+    #[test]
+    fn test_group_orders_oco() {
+        let legs = [
+            terms(false, OrderType::Limit, 10),
+            terms(false, OrderType::Stop, 10),
+        ];
+        let orders = group_orders(GroupType::Oco, &legs, 1_000).unwrap();
+        assert!(orders
+            .iter()
+            .all(|order| order.group_role == GroupRole::Leg && order.status == OrderStatus::Open));
+
+        assert_eq!(
+            group_orders(GroupType::Oco, &legs[..1], 1_000),
+            Err(ReturnFlags::ServerPlaceOrderGroupInvMsg)
+        );
+        let other_stock = Order {
+            stock_symbol: "MSFT".to_string(),
+            ..legs[1].clone()
+        };
+        assert_eq!(
+            group_orders(GroupType::Oco, &[legs[0].clone(), other_stock], 1_000),
+            Err(ReturnFlags::ServerPlaceOrderGroupInvMsg)
+        );
+    }
+
+    #[test]
+    fn test_group_orders_bracket() {
+        let bracket = [
+            terms(true, OrderType::Limit, 10),
+            terms(false, OrderType::Limit, 1),
+            terms(false, OrderType::TrailingStop, 1),
+        ];
+        /* trailing stops need a trail */
+        assert_eq!(
+            group_orders(GroupType::Bracket, &bracket, 1_000),
+            Err(ReturnFlags::ServerPlaceOrderInvMsg)
+        );
+
+        let bracket = [
+            bracket[0].clone(),
+            bracket[1].clone(),
+            terms(false, OrderType::Stop, 1),
+        ];
+        let orders = group_orders(GroupType::Bracket, &bracket, 1_000).unwrap();
+        assert_eq!(orders[0].group_role, GroupRole::Entry);
+        assert_eq!(orders[0].status, OrderStatus::Open);
+        for exit in &orders[1..] {
+            assert_eq!(exit.group_role, GroupRole::Leg);
+            assert_eq!(exit.status, OrderStatus::Pending);
+            assert_eq!(exit.stock_amount, 10);
+        }
+
+        /* the take-profit and the stop-loss cannot be swapped */
+        let swapped = [bracket[0].clone(), bracket[2].clone(), bracket[1].clone()];
+        assert_eq!(
+            group_orders(GroupType::Bracket, &swapped, 1_000),
+            Err(ReturnFlags::ServerPlaceOrderGroupInvMsg)
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use log::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::common::account::fill::Fill;
+use crate::common::generic::stock_val::StockVal;
+use crate::common::message::request::PurchaseAssetRequest;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::account::execution::execute_buy;
+use crate::server::db::cmd::get_company::get_company_from_db;
+use crate::server::db::cmd::get_stock::get_stock_from_db_latest;
+use crate::server::ds::trading_config::trading_config;
+use crate::server::engine::engine_conn::{
+    begin_transaction, commit_transaction, connect_trade_conn,
+};
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Buys shares of a stock at the market price.
+///
+/// The order is filled at once, at the ask of the latest ```StockVal``` of the stock slipped by
+/// the configured ```CostModel```. The cost and the fees are debited from the cash balance of
+/// the account, and the purchase is rejected if the balance is too low. The debit, the
+/// ```Transaction``` and the open ```Position``` of the purchase are written in a single
+/// database transaction, on a connection of their own.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use for reads.
+/// purchase_req - The purchase, with the JWT token of the account buying.
+///
+/// Returns: the ```Fill``` of the order on success, ```ReturnFlags::ServerAccInsufficientCash```
+/// if the account cannot pay for the shares, ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let fill = acc_purchase_asset(&sql_conn, &purchase_req).await?;
+///     info!("bought {} {} at {}", fill.quantity, fill.stock_symbol, fill.price);
+/// ```
+pub async fn acc_purchase_asset(
+    sql_conn: &tokio_postgres::Client,
+    purchase_req: &PurchaseAssetRequest,
+) -> Result<Fill, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(purchase_req.auth_jwt.clone()) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_PURCHASE_ASSET_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+    if purchase_req.quantity <= 0 {
+        return Err(ReturnFlags::ServerPurchaseAssetInvMsg);
+    }
+
+    /* only listed companies can be traded */
+    get_company_from_db(sql_conn, &purchase_req.symbol).await?;
+    let quote = get_stock_from_db_latest(sql_conn, &purchase_req.symbol).await?;
+
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+    let fill = market_buy_fill(
+        &purchase_req.symbol,
+        purchase_req.quantity,
+        &quote,
+        time_epoch,
+    )?;
+
+    let mut trade_conn = connect_trade_conn().await?;
+    let db_transaction = begin_transaction(&mut trade_conn).await?;
+    execute_buy(&db_transaction, token.user_id, &fill).await?;
+    commit_transaction(db_transaction).await?;
+
+    Ok(fill)
+}
+
+/// Prices a market buy from the latest value of the stock.
+///
+/// Returns: the ```Fill``` at the ask price slipped by the configured ```CostModel```,
+/// ```ReturnFlags::ServerPurchaseAssetFailed``` if the stock has no usable ask.
+fn market_buy_fill(
+    symbol: &str,
+    quantity: i64,
+    quote: &StockVal,
+    time_epoch: i64,
+) -> Result<Fill, ReturnFlags> {
+    if !quote.ask_price.is_finite() || quote.ask_price <= 0.0 {
+        warn!("ACC_PURCHASE_ASSET_NO_ASK: {}", symbol);
+        return Err(ReturnFlags::ServerPurchaseAssetFailed);
+    }
+    Ok(Fill {
+        stock_symbol: symbol.to_string(),
+        quantity,
+        price: trading_config().cost_model.fill_price(
+            true,
+            quote.ask_price,
+            quantity,
+            quote.volume,
+        ),
+        time_epoch,
+        realized_pnl: 0.0,
+        order_id: 0,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_market_buy_fill() {
+        let quote = StockVal {
+            ask_price: 12.34,
+            bid_price: 12.30,
+            ..StockVal::default()
+        };
+        let fill = market_buy_fill("AAPL", 3, &quote, 1_000).unwrap();
+        assert_eq!(
+            fill,
+            Fill {
+                stock_symbol: "AAPL".to_string(),
+                quantity: 3,
+                price: 12.34,
+                time_epoch: 1_000,
+                realized_pnl: 0.0,
+                order_id: 0,
+            }
+        );
+
+        /* no ask, no fill */
+        let quote = StockVal::default();
+        assert_eq!(
+            market_buy_fill("AAPL", 3, &quote, 1_000),
+            Err(ReturnFlags::ServerPurchaseAssetFailed)
+        );
+    }
+}
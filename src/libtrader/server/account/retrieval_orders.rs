@@ -0,0 +1,40 @@
+use log::warn;
+
+use crate::common::account::order::Order;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_orders::get_user_orders;
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Retrieves the orders of an authorized account.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// auth_jwt - The JWT token of the account.
+/// open - Whether to retrieve the open and pending orders, oldest first, or the order history,
+/// newest first.
+///
+/// Returns: the orders on success, ```ReturnFlags``` on error.
+///
+/// Example:
+/// ```rust
+///     let history = acc_retrieve_orders(&sql_conn, auth_jwt, false).await?;
+/// ```
+pub async fn acc_retrieve_orders(
+    sql_conn: &tokio_postgres::Client,
+    auth_jwt: String,
+    open: bool,
+) -> Result<Vec<Order>, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(auth_jwt) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_RETRIEVE_ORDERS_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+
+    get_user_orders(sql_conn, token.user_id, open).await
+}
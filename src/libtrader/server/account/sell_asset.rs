@@ -0,0 +1,83 @@
+use log::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::common::account::fill::Fill;
+use crate::common::message::request::SellAssetRequest;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::account::execution::execute_sell;
+use crate::server::db::cmd::get_stock::get_stock_from_db_latest;
+use crate::server::ds::trading_config::trading_config;
+use crate::server::engine::engine_conn::{
+    begin_transaction, commit_transaction, connect_trade_conn,
+};
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Sells shares of a stock at the market price.
+///
+/// The order is filled at once, at the bid of the latest ```StockVal``` of the stock slipped by
+/// the configured ```CostModel```. The shares are taken from the open positions of the account
+/// first in first out, closing them fully or partially, the proceeds are credited to the cash
+/// balance of the account, and the sale is recorded as a ```Transaction```. Everything is
+/// written in a single database transaction, on a connection of their own.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use for reads.
+/// sell_req - The sale, with the JWT token of the account selling.
+///
+/// Returns: the ```Fill``` of the order with its realized profit on success,
+/// ```ReturnFlags::ServerSellAssetOversold``` if the account holds fewer shares than sold,
+/// ```ReturnFlags``` on other errors.
+///
+/// Example:
+/// ```rust
+///     let fill = acc_sell_asset(&sql_conn, &sell_req).await?;
+///     info!("sold {} {}, made {}", fill.quantity, fill.stock_symbol, fill.realized_pnl);
+/// ```
+pub async fn acc_sell_asset(
+    sql_conn: &tokio_postgres::Client,
+    sell_req: &SellAssetRequest,
+) -> Result<Fill, ReturnFlags> {
+    /* verify JWT token */
+    let token = match verify_jwt_token(sell_req.auth_jwt.clone()) {
+        Ok(token) => token,
+        Err(_) => {
+            warn!("ACC_SELL_ASSET_UNAUTH_TOKEN");
+            return Err(ReturnFlags::ServerAccUnauthorized);
+        }
+    };
+    if sell_req.quantity <= 0 {
+        return Err(ReturnFlags::ServerSellAssetInvMsg);
+    }
+
+    let quote = get_stock_from_db_latest(sql_conn, &sell_req.symbol).await?;
+    if !quote.bid_price.is_finite() || quote.bid_price <= 0.0 {
+        warn!("ACC_SELL_ASSET_NO_BID: {}", sell_req.symbol);
+        return Err(ReturnFlags::ServerSellAssetFailed);
+    }
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+
+    let mut trade_conn = connect_trade_conn().await?;
+    let db_transaction = begin_transaction(&mut trade_conn).await?;
+    let mut fill = Fill {
+        stock_symbol: sell_req.symbol.clone(),
+        quantity: sell_req.quantity,
+        price: trading_config().cost_model.fill_price(
+            false,
+            quote.bid_price,
+            sell_req.quantity,
+            quote.volume,
+        ),
+        time_epoch,
+        realized_pnl: 0.0,
+        order_id: 0,
+    };
+    execute_sell(&db_transaction, token.user_id, &mut fill, None).await?;
+    commit_transaction(db_transaction).await?;
+
+    Ok(fill)
+}
@@ -0,0 +1,93 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Reason of a change of a cash balance, stored in ```accounts_schema.cash_ledger```.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CashReason {
+    Deposit,
+    Buy,
+    Sell,
+    Fee,
+    Dividend,
+}
+
+impl CashReason {
+    /// Returns the name of the reason as stored in the ledger.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CashReason::Deposit => "deposit",
+            CashReason::Buy => "buy",
+            CashReason::Sell => "sell",
+            CashReason::Fee => "fee",
+            CashReason::Dividend => "dividend",
+        }
+    }
+}
+
+/// Changes the cash balance of an account and records the change in the ledger.
+///
+/// The balance never goes below zero, debits larger than the balance are rejected without
+/// changing anything. The account row stays locked until the end of the database transaction,
+/// so concurrent trades of an account are applied one after the other.
+/// Should be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// user_id - The ID of the account.
+/// amount - The amount to credit, or debit if negative, in cents.
+/// reason - The reason of the change.
+/// stock_symbol - The stock the change relates to, empty if none.
+/// time_epoch - The unix epoch of the change.
+///
+/// Returns: the new balance in cents on success, ```ReturnFlags::ServerAccInsufficientCash```
+/// if the balance is too low, ```ReturnFlags::ServerDbWriteFailed``` on other errors.
+///
+/// Example:
+/// ```rust
+///     apply_cash(&db_transaction, user_id, -cost, CashReason::Buy, "AAPL", time_epoch).await?;
+/// ```
+pub async fn apply_cash<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    amount: i64,
+    reason: CashReason,
+    stock_symbol: &str,
+    time_epoch: i64,
+) -> Result<i64, ReturnFlags> {
+    let rows = sql_conn
+        .query(
+            "UPDATE accounts_schema.accounts SET cash_balance = cash_balance + $2
+                WHERE id = $1 AND cash_balance + $2 >= 0
+                RETURNING cash_balance",
+            &[&user_id, &amount],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)?;
+    let balance: i64 = match rows.first() {
+        Some(row) => row
+            .try_get(0)
+            .map_err(|_| ReturnFlags::ServerDbWriteFailed)?,
+        None => return Err(ReturnFlags::ServerAccInsufficientCash),
+    };
+
+    sql_conn
+        .execute(
+            "INSERT INTO accounts_schema.cash_ledger
+                (user_id, amount, reason, stock_symbol, balance, time_epoch)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+            &[
+                &user_id,
+                &amount,
+                &reason.as_str(),
+                &stock_symbol,
+                &balance,
+                &time_epoch,
+            ],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)?;
+
+    Ok(balance)
+}
@@ -0,0 +1,63 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::order::Order;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Stores a new order on the postgres SQL database.
+///
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// user_id - The ID of the account placing the order.
+/// order - The order, its ```id``` is ignored.
+///
+/// Returns: the id of the new order on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     order.id = create_order(&sql_conn, user_id, &order).await?;
+/// ```
+pub async fn create_order<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    order: &Order,
+) -> Result<i64, ReturnFlags> {
+    let rows = sql_conn
+        .query(
+            "INSERT INTO accounts_schema.orders
+                (user_id, stock_symbol, is_buy, order_type, stock_price, stock_amount,
+                stock_filled, is_filled, status, created_epoch, stop_price, trail_amount,
+                water_mark, is_triggered, group_id, group_role, time_in_force, expire_epoch)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                $17, $18)
+                RETURNING id",
+            &[
+                &user_id,
+                &order.stock_symbol,
+                &order.is_buy,
+                &order.order_type.as_str(),
+                &order.stock_price,
+                &order.stock_amount,
+                &order.stock_filled,
+                &order.is_filled,
+                &order.status.as_str(),
+                &order.created_epoch,
+                &order.stop_price,
+                &order.trail_amount,
+                &order.water_mark,
+                &order.is_triggered,
+                &order.group_id,
+                &order.group_role.as_str(),
+                &order.time_in_force.as_str(),
+                &order.expire_epoch,
+            ],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)?;
+    match rows.first() {
+        Some(row) => row.try_get(0).map_err(|_| ReturnFlags::ServerDbWriteFailed),
+        None => Err(ReturnFlags::ServerDbWriteFailed),
+    }
+}
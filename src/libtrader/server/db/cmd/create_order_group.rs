@@ -0,0 +1,43 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::order_group::GroupType;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Stores a new order group on the postgres SQL database.
+///
+/// The orders of the group are stored separately, pointing to the group by its id.
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// user_id - The ID of the account placing the group.
+/// group_type - The kind of the group.
+/// created_epoch - The unix epoch at which the group was placed.
+///
+/// Returns: the id of the new group on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let group_id = create_order_group(&db_transaction, user_id, GroupType::Oco, now).await?;
+/// ```
+pub async fn create_order_group<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    group_type: GroupType,
+    created_epoch: i64,
+) -> Result<i64, ReturnFlags> {
+    let rows = sql_conn
+        .query(
+            "INSERT INTO accounts_schema.order_groups (user_id, group_type, created_epoch)
+                VALUES ($1, $2, $3)
+                RETURNING id",
+            &[&user_id, &group_type.as_str(), &created_epoch],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)?;
+    match rows.first() {
+        Some(row) => row.try_get(0).map_err(|_| ReturnFlags::ServerDbWriteFailed),
+        None => Err(ReturnFlags::ServerDbWriteFailed),
+    }
+}
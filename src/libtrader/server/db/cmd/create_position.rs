@@ -1,26 +1,30 @@
 use crate::common::account::position::Position;
 use crate::common::misc::return_flags::ReturnFlags;
+use tokio_postgres::GenericClient;
 
 /// Creates a position on the postgre SQL database
 ///
 /// Takes in the position to insert to the database.
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
 /// Should be used in Async contexts.
 ///
 /// Arguments:
-/// sql_conn - The SQL connection to use.
+/// sql_conn - The SQL connection or database transaction to use.
 /// user_id - ID to create position for.
+/// order_id - The id of the order opening the position, ```0``` for market purchases.
 /// position - The position to use.
 ///
 /// Example:
 /// ```rust
-///     match create_position(Position::default()) {
+///     match create_position(&sql_conn, user_id, 0, Position::default()).await {
 ///         Ok(_) => {},
 ///         Err(err) => panic!("TEST_CMD_CREATE_PORTFOLIO_FAILED: {}", err)
 ///     }
 /// ```
-pub async fn create_position(
-    sql_conn: &mut tokio_postgres::Client,
+pub async fn create_position<C: GenericClient>(
+    sql_conn: &C,
     user_id: i64,
+    order_id: i64,
     position: Position,
 ) -> Result<(), ReturnFlags> {
     /*
@@ -30,11 +34,11 @@ pub async fn create_position(
     /* insert position */
     match sql_conn.execute("INSERT INTO portfolio_schema.positions 
                          (user_id, stock_symbol, stock_open_amount, stock_open_price, stock_open_cost,
-                         stock_close_amount, stock_close_price, open_epoch, close_epoch, is_buy, is_open)
-                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                         stock_close_amount, stock_close_price, open_epoch, close_epoch, is_buy, is_open, order_id)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
                          &[&user_id, &position.stock_symbol, &position.stock_open_amount, &position.stock_open_price,
                          &position.stock_open_cost, &position.stock_close_amount, &position.stock_close_price,
-                         &position.open_epoch, &position.close_epoch, &position.is_buy, &position.is_open]).await {
+                         &position.open_epoch, &position.close_epoch, &position.is_buy, &position.is_open, &order_id]).await {
         Ok(_rows) => Ok(()),
         Err(_) => Err(ReturnFlags::ServerDbCreatePositionFailed),
     }
@@ -0,0 +1,54 @@
+use crate::common::generic::stock_val::StockVal;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Stores a new stock value on the postgres SQL database.
+///
+/// Inserts the value in the stock table of the ```asset_schema``` schema. Stored values are
+/// published to the subscribed clients and the matching engine by ```run_quote_poller()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// stock_name - The name of the stock table.
+/// stock_val - The value to store.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+///
+/// Example:
+/// ```rust
+///    match create_stock_val(&sql_conn, "AAPL", &stock_val).await {
+///        Ok(()) => info!("stored stock value"),
+///        Err(err) => error!("failed to store stock value {}", err),
+///    }
+/// ```
+pub async fn create_stock_val(
+    sql_conn: &tokio_postgres::Client,
+    stock_name: &str,
+    stock_val: &StockVal,
+) -> Result<(), ReturnFlags> {
+    /*
+     * Inserts a stock value in the stock table in assets schema.
+     */
+    match sql_conn
+        .execute(
+            format!(
+                "INSERT INTO asset_schema.{} \
+                        (isin, time_epoch, ask_price, bid_price, volume) \
+                        VALUES ($1, $2, $3, $4, $5)",
+                stock_name
+            )
+            .as_str(),
+            &[
+                &stock_val.isin,
+                &stock_val.time_epoch,
+                &stock_val.ask_price,
+                &stock_val.bid_price,
+                &stock_val.volume,
+            ],
+        )
+        .await
+    {
+        Ok(_rows) => Ok(()),
+        Err(_) => Err(ReturnFlags::ServerDbWriteFailed),
+    }
+}
@@ -1,25 +1,27 @@
 use crate::common::account::transaction::Transaction;
 use crate::common::misc::return_flags::ReturnFlags;
+use tokio_postgres::GenericClient;
 
 /// Creates a transaction on the postgre SQL database
 ///
 /// Takes in the transaction and a userId to insert to the database.
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
 /// Should be used in Async contexts.
 ///
 /// Arguments:
-/// sql_conn - The SQL connection to use.
+/// sql_conn - The SQL connection or database transaction to use.
 /// user_id - ID to use for the new transaction.
 /// transaction - The transaction to use.
 ///
 /// Example:
 /// ```rust
-///     match create_transaction(Position::default()) {
+///     match create_transaction(&sql_conn, user_id, &Transaction::default()).await {
 ///         Ok(_) => {},
 ///         Err(err) => panic!("TEST_CMD_CREATE_TRANSACTION_FAILED: {}", err)
 ///     }
 /// ```
-pub async fn create_transaction(
-    sql_conn: &mut tokio_postgres::Client,
+pub async fn create_transaction<C: GenericClient>(
+    sql_conn: &C,
     user_id: i64,
     transaction: &Transaction,
 ) -> Result<(), ReturnFlags> {
@@ -31,14 +33,15 @@ pub async fn create_transaction(
     match sql_conn
         .execute(
             "INSERT INTO accounts_schema.transactions 
-                         (user_id, stock_symbol, shares_size, shares_cost, is_buy) 
-                         VALUES ($1, $2, $3, $4, $5)",
+                         (user_id, stock_symbol, shares_size, shares_cost, is_buy, kind) 
+                         VALUES ($1, $2, $3, $4, $5, $6)",
             &[
                 &user_id,
                 &transaction.stock_symbol,
                 &transaction.shares_size,
                 &transaction.shares_cost,
                 &transaction.is_buy,
+                &transaction.kind.as_str(),
             ],
         )
         .await
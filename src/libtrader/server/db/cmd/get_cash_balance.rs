@@ -0,0 +1,32 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Returns the cash balance of an account, in cents.
+///
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// user_id - The ID of the account.
+///
+/// Returns: the balance on success, ```ReturnFlags::ServerGetUserIdNotFound``` if the account
+/// does not exist, ```ReturnFlags::ServerDbQueryFailed``` if the query failed.
+pub async fn get_cash_balance<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+) -> Result<i64, ReturnFlags> {
+    let rows = sql_conn
+        .query(
+            "SELECT cash_balance FROM accounts_schema.accounts WHERE id = $1",
+            &[&user_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    match rows.first() {
+        Some(row) => row
+            .try_get(0)
+            .map_err(|_| ReturnFlags::ServerGetUserIdNotFound),
+        None => Err(ReturnFlags::ServerGetUserIdNotFound),
+    }
+}
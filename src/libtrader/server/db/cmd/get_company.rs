@@ -4,51 +4,77 @@ use crate::common::misc::return_flags::ReturnFlags;
 /// Returns a company from the postgres SQL database.
 ///
 /// Takes in a company symbol and returns a company.
-/// Shuold be used in Async contexts
+/// Should be used in Async contexts.
 ///
 /// Arguments:
 /// sql_conn - The SQL connection to use.
 /// search_symbol - The specific company symbol to find.
 ///
-/// Returns: a reference to the found company on success, and a string containing the reason of
-/// failure on error.
+/// Returns: the found company on success, ```ReturnFlags::ServerDbSearchCompanyNotFound``` if
+/// no company has the symbol, ```ReturnFlags::ServerDbQueryFailed``` if the query failed.
 ///
 /// Example:
 /// ```rust
-///    match get_company_from_db("AAPL".to_string()) {
+///    match get_company_from_db(&sql_conn, "AAPL").await {
 ///        Ok(found_company) => info!("we found it! {:?}", found_company),
 ///        Err(err) => error!("we must found the sacred company! err: {}", err),
 ///    }
 /// ```
 pub async fn get_company_from_db(
-    sql_conn: &mut tokio_postgres::Client,
+    sql_conn: &tokio_postgres::Client,
     searched_symbol: &str,
 ) -> Result<Company, ReturnFlags> {
     /*
      * Returns company entry from database
      */
     // Connect to database.
-    match sql_conn
+    let rows = sql_conn
         .query(
             "SELECT * FROM public.companies WHERE symbol=$1",
             &[&searched_symbol],
         )
         .await
-    {
-        Ok(row) => {
-            let mut found_company: Company = Company::default();
-            found_company.id = row[0].get(0);
-            found_company.symbol = row[0].get(1);
-            found_company.isin = row[0].get(2);
-            found_company.company_name = row[0].get(3);
-            found_company.primary_exchange = row[0].get(4);
-            found_company.sector = row[0].get(5);
-            found_company.industry = row[0].get(6);
-            found_company.primary_sic_code = row[0].get(7);
-            found_company.employees = row[0].get(8);
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    let row = rows
+        .first()
+        .ok_or(ReturnFlags::ServerDbSearchCompanyNotFound)?;
 
-            return Ok(found_company);
-        }
-        Err(_) => Err(ReturnFlags::ServerDbSearchCompanyNotFound),
-    }
+    let read = |_| ReturnFlags::ServerDbSearchCompanyNotFound;
+    Ok(Company {
+        id: row.try_get(0).map_err(read)?,
+        symbol: row.try_get(1).map_err(read)?,
+        isin: row.try_get(2).map_err(read)?,
+        company_name: row.try_get(3).map_err(read)?,
+        primary_exchange: row.try_get(4).map_err(read)?,
+        sector: row.try_get(5).map_err(read)?,
+        industry: row.try_get(6).map_err(read)?,
+        primary_sic_code: row.try_get(7).map_err(read)?,
+        employees: row.try_get(8).map_err(read)?,
+    })
+}
+
+/// Returns the symbols of every listed company.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+///
+/// Returns: the symbols on success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     for symbol in get_company_symbols(&sql_conn).await? { /* ... */ }
+/// ```
+pub async fn get_company_symbols(
+    sql_conn: &tokio_postgres::Client,
+) -> Result<Vec<String>, ReturnFlags> {
+    sql_conn
+        .query("SELECT symbol FROM public.companies ORDER BY id", &[])
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(|row| row.try_get(0))
+        .collect::<Result<_, _>>()
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)
 }
@@ -0,0 +1,82 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Returns the ids of the dividends due and not paid yet.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// time_epoch - The current unix epoch.
+///
+/// Returns: the ids of the unpaid dividends payable at or before ```time_epoch``` on success,
+/// ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     for dividend_id in get_due_dividends(&sql_conn, now).await? { /* ... */ }
+/// ```
+pub async fn get_due_dividends<C: GenericClient>(
+    sql_conn: &C,
+    time_epoch: i64,
+) -> Result<Vec<i64>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT id FROM public.dividends
+                WHERE NOT is_paid AND pay_epoch <= $1
+                ORDER BY id",
+            &[&time_epoch],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(|row| row.try_get(0))
+        .collect::<Result<_, _>>()
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)
+}
+
+/// Returns an unpaid dividend, locking it until the end of the database transaction.
+///
+/// Concurrent payments of the dividend wait for the lock, and find it paid once they get it.
+/// Should be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// dividend_id - The id of the dividend.
+///
+/// Returns: the symbol of the stock and the dividend of one share in cents on success,
+/// ```None``` if no unpaid dividend has the id, ```ReturnFlags::ServerDbQueryFailed``` on
+/// error.
+///
+/// Example:
+/// ```rust
+///     if let Some((symbol, cents_per_share)) = get_dividend_for_update(&db_transaction, id).await? {
+///         pay_dividend(&db_transaction, &symbol, cents_per_share, time_epoch).await?;
+///         set_dividend_paid(&db_transaction, id).await?;
+///     }
+/// ```
+pub async fn get_dividend_for_update<C: GenericClient>(
+    sql_conn: &C,
+    dividend_id: i64,
+) -> Result<Option<(String, i64)>, ReturnFlags> {
+    let row = sql_conn
+        .query_opt(
+            "SELECT stock_symbol, cents_per_share FROM public.dividends
+                WHERE id = $1 AND NOT is_paid
+                FOR UPDATE",
+            &[&dividend_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    match row {
+        Some(row) => Ok(Some((
+            row.try_get(0)
+                .map_err(|_| ReturnFlags::ServerDbQueryFailed)?,
+            row.try_get(1)
+                .map_err(|_| ReturnFlags::ServerDbQueryFailed)?,
+        ))),
+        None => Ok(None),
+    }
+}
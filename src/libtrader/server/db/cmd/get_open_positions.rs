@@ -0,0 +1,110 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::position::Position;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Reads a position out of a row of ```portfolio_schema.positions```.
+///
+/// Columns are read by name, so the order of the columns in the table does not matter.
+///
+/// Arguments:
+/// row - The row to read.
+///
+/// Returns: the id of the position row and the position, ```tokio_postgres::Error``` if a
+/// column is missing or has the wrong type.
+pub fn position_from_row(
+    row: &tokio_postgres::Row,
+) -> Result<(i64, Position), tokio_postgres::Error> {
+    let position = Position {
+        is_buy: row.try_get("is_buy")?,
+        stock_symbol: row.try_get("stock_symbol")?,
+        stock_open_amount: row.try_get("stock_open_amount")?,
+        stock_open_price: row.try_get("stock_open_price")?,
+        stock_open_cost: row.try_get("stock_open_cost")?,
+        stock_close_amount: row.try_get("stock_close_amount")?,
+        stock_close_price: row.try_get("stock_close_price")?,
+        stock_close_cost: row.try_get("stock_close_cost")?,
+        open_epoch: row.try_get("open_epoch")?,
+        close_epoch: row.try_get("close_epoch")?,
+        is_open: row.try_get("is_open")?,
+    };
+    Ok((row.try_get("id")?, position))
+}
+
+/// Returns the open long positions of a user on a stock, oldest first.
+///
+/// The rows are locked until the end of the database transaction, so concurrent sales of the
+/// same shares wait for each other. Must be used within a database transaction.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// user_id - The ID of the user owning the positions.
+/// stock_symbol - The symbol of the stock.
+///
+/// Returns: the ids of the position rows and the positions, ordered by ```open_epoch```, on
+/// success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let lots = get_open_positions_for_update(&db_transaction, user_id, "AAPL").await?;
+/// ```
+pub async fn get_open_positions_for_update<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    stock_symbol: &str,
+) -> Result<Vec<(i64, Position)>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT * FROM portfolio_schema.positions
+                WHERE user_id = $1 AND stock_symbol = $2 AND is_open AND is_buy
+                ORDER BY open_epoch, id
+                FOR UPDATE",
+            &[&user_id, &stock_symbol],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(position_from_row)
+        .collect::<Result<_, _>>()
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)
+}
+
+/// Returns the open long positions opened by the fills of an order, oldest first.
+///
+/// The rows are locked until the end of the database transaction, see
+/// ```get_open_positions_for_update()```. Must be used within a database transaction.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// user_id - The ID of the user owning the positions.
+/// order_id - The id of the order that opened the positions.
+///
+/// Returns: the ids of the position rows and the positions, ordered by ```open_epoch```, on
+/// success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let lots = get_order_positions_for_update(&db_transaction, user_id, entry.id).await?;
+/// ```
+pub async fn get_order_positions_for_update<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    order_id: i64,
+) -> Result<Vec<(i64, Position)>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT * FROM portfolio_schema.positions
+                WHERE user_id = $1 AND order_id = $2 AND is_open AND is_buy
+                ORDER BY open_epoch, id
+                FOR UPDATE",
+            &[&user_id, &order_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(position_from_row)
+        .collect::<Result<_, _>>()
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)
+}
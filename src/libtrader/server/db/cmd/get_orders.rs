@@ -0,0 +1,284 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::order::{GroupRole, Order, OrderStatus, OrderType, TimeInForce};
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Reads an order out of a row of ```accounts_schema.orders```.
+///
+/// Arguments:
+/// row - The row to read.
+///
+/// Returns: the ID of the account owning the order and the order, ```None``` if a column is
+/// missing or invalid.
+pub fn order_from_row(row: &tokio_postgres::Row) -> Option<(i64, Order)> {
+    let order = Order {
+        id: row.try_get("id").ok()?,
+        is_buy: row.try_get("is_buy").ok()?,
+        stock_symbol: row.try_get("stock_symbol").ok()?,
+        order_type: OrderType::from_db(row.try_get("order_type").ok()?)?,
+        stock_price: row.try_get("stock_price").ok()?,
+        stock_amount: row.try_get("stock_amount").ok()?,
+        stock_filled: row.try_get("stock_filled").ok()?,
+        is_filled: row.try_get("is_filled").ok()?,
+        status: OrderStatus::from_db(row.try_get("status").ok()?)?,
+        created_epoch: row.try_get("created_epoch").ok()?,
+        stop_price: row.try_get("stop_price").ok()?,
+        trail_amount: row.try_get("trail_amount").ok()?,
+        water_mark: row.try_get("water_mark").ok()?,
+        is_triggered: row.try_get("is_triggered").ok()?,
+        group_id: row.try_get("group_id").ok()?,
+        group_role: GroupRole::from_db(row.try_get("group_role").ok()?)?,
+        time_in_force: TimeInForce::from_db(row.try_get("time_in_force").ok()?)?,
+        expire_epoch: row.try_get("expire_epoch").ok()?,
+    };
+    Some((row.try_get("user_id").ok()?, order))
+}
+
+/// Returns the open orders on a stock, oldest first.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// stock_symbol - The symbol of the stock.
+///
+/// Returns: the IDs of the accounts owning the orders and the orders on success,
+/// ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     for (user_id, order) in get_open_orders(&sql_conn, "AAPL").await? { /* ... */ }
+/// ```
+pub async fn get_open_orders<C: GenericClient>(
+    sql_conn: &C,
+    stock_symbol: &str,
+) -> Result<Vec<(i64, Order)>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT * FROM accounts_schema.orders
+                WHERE stock_symbol = $1 AND status = $2
+                ORDER BY created_epoch, id",
+            &[&stock_symbol, &OrderStatus::Open.as_str()],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(order_from_row)
+        .collect::<Option<_>>()
+        .ok_or(ReturnFlags::ServerDbQueryFailed)
+}
+
+/// Returns an order.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// order_id - The id of the order.
+///
+/// Returns: the ID of the account owning the order and the order on success, ```None``` if no
+/// order has the id, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let (user_id, order) = get_order(&sql_conn, order_id).await?.unwrap();
+/// ```
+pub async fn get_order<C: GenericClient>(
+    sql_conn: &C,
+    order_id: i64,
+) -> Result<Option<(i64, Order)>, ReturnFlags> {
+    let rows = sql_conn
+        .query(
+            "SELECT * FROM accounts_schema.orders WHERE id = $1",
+            &[&order_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    match rows.first() {
+        Some(row) => order_from_row(row)
+            .map(Some)
+            .ok_or(ReturnFlags::ServerDbQueryFailed),
+        None => Ok(None),
+    }
+}
+
+/// Returns an order, locked until the end of the database transaction.
+///
+/// Must be used within a database transaction.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// order_id - The id of the order.
+///
+/// Returns: the ID of the account owning the order and the order on success, ```None``` if no
+/// order has the id, ```ReturnFlags::ServerDbQueryFailed``` on error.
+pub async fn get_order_for_update<C: GenericClient>(
+    sql_conn: &C,
+    order_id: i64,
+) -> Result<Option<(i64, Order)>, ReturnFlags> {
+    let rows = sql_conn
+        .query(
+            "SELECT * FROM accounts_schema.orders WHERE id = $1 FOR UPDATE",
+            &[&order_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    match rows.first() {
+        Some(row) => order_from_row(row)
+            .map(Some)
+            .ok_or(ReturnFlags::ServerDbQueryFailed),
+        None => Ok(None),
+    }
+}
+
+/// Returns the orders of a group, locked until the end of the database transaction.
+///
+/// Must be used within a database transaction.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// group_id - The id of the ```OrderGroup```.
+///
+/// Returns: the IDs of the accounts owning the orders and the orders, in the order they were
+/// placed, on success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let group = get_group_orders_for_update(&db_transaction, order.group_id).await?;
+/// ```
+pub async fn get_group_orders_for_update<C: GenericClient>(
+    sql_conn: &C,
+    group_id: i64,
+) -> Result<Vec<(i64, Order)>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT * FROM accounts_schema.orders WHERE group_id = $1 ORDER BY id FOR UPDATE",
+            &[&group_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(order_from_row)
+        .collect::<Option<_>>()
+        .ok_or(ReturnFlags::ServerDbQueryFailed)
+}
+
+/// Returns the orders of a group.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// group_id - The id of the ```OrderGroup```.
+///
+/// Returns: the orders, in the order they were placed, on success,
+/// ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let orders = get_group_orders(&sql_conn, group_id).await?;
+/// ```
+pub async fn get_group_orders<C: GenericClient>(
+    sql_conn: &C,
+    group_id: i64,
+) -> Result<Vec<Order>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT * FROM accounts_schema.orders WHERE group_id = $1 ORDER BY id",
+            &[&group_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(|row| order_from_row(row).map(|(_, order)| order))
+        .collect::<Option<_>>()
+        .ok_or(ReturnFlags::ServerDbQueryFailed)
+}
+
+/// Returns the ids of the orders whose time in force ran out.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// time_epoch - The current unix epoch.
+///
+/// Returns: the ids of the open and pending orders expiring at or before ```time_epoch``` on
+/// success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     for order_id in get_expired_orders(&sql_conn, now).await? { /* ... */ }
+/// ```
+pub async fn get_expired_orders<C: GenericClient>(
+    sql_conn: &C,
+    time_epoch: i64,
+) -> Result<Vec<i64>, ReturnFlags> {
+    sql_conn
+        .query(
+            "SELECT id FROM accounts_schema.orders
+                WHERE expire_epoch <> 0 AND expire_epoch <= $1
+                AND status IN ($2, $3)
+                ORDER BY id",
+            &[
+                &time_epoch,
+                &OrderStatus::Open.as_str(),
+                &OrderStatus::Pending.as_str(),
+            ],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(|row| row.try_get(0))
+        .collect::<Result<_, _>>()
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)
+}
+
+/// Returns the orders of an account.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// user_id - The ID of the account.
+/// open - Whether to return the open and pending orders, in the order they were placed, or the
+/// orders in a final state, newest first.
+///
+/// Returns: the orders on success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     let open_orders = get_user_orders(&sql_conn, token.user_id, true).await?;
+/// ```
+pub async fn get_user_orders<C: GenericClient>(
+    sql_conn: &C,
+    user_id: i64,
+    open: bool,
+) -> Result<Vec<Order>, ReturnFlags> {
+    let query = if open {
+        "SELECT * FROM accounts_schema.orders
+            WHERE user_id = $1 AND status IN ($2, $3)
+            ORDER BY id"
+    } else {
+        "SELECT * FROM accounts_schema.orders
+            WHERE user_id = $1 AND status NOT IN ($2, $3)
+            ORDER BY id DESC"
+    };
+    sql_conn
+        .query(
+            query,
+            &[
+                &user_id,
+                &OrderStatus::Open.as_str(),
+                &OrderStatus::Pending.as_str(),
+            ],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?
+        .iter()
+        .map(|row| order_from_row(row).map(|(_, order)| order))
+        .collect::<Option<_>>()
+        .ok_or(ReturnFlags::ServerDbQueryFailed)
+}
@@ -1,6 +1,13 @@
 use crate::common::generic::stock_val::StockVal;
 use crate::common::misc::return_flags::ReturnFlags;
 
+/// Returns whether a symbol can be used as a stock table name.
+///
+/// The symbol is part of the query text, so only non-empty alphanumeric symbols are allowed.
+fn is_valid_symbol(symbol: &str) -> bool {
+    !symbol.is_empty() && symbol.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
 /// Returns the whole stock data from the postgres SQL database.
 ///
 /// Takes in a stock symbol and returns the whole data entries of the searched stock.
@@ -22,12 +29,15 @@ use crate::common::misc::return_flags::ReturnFlags;
 ///   };
 /// ```
 pub async fn get_stock_from_db(
-    sql_conn: &mut tokio_postgres::Client,
+    sql_conn: &tokio_postgres::Client,
     searched_symbol: &str,
 ) -> Result<Vec<StockVal>, ReturnFlags> {
     /*
      * Returns all stock values from database.
      */
+    if !is_valid_symbol(searched_symbol) {
+        return Err(ReturnFlags::ServerDbSearchStockNotFound);
+    }
 
     // Query database for table.
     let mut stocks: Vec<StockVal> = Vec::new();
@@ -77,13 +87,16 @@ pub async fn get_stock_from_db(
 ///     };
 /// ```
 pub async fn get_stock_from_db_since_epoch(
-    sql_conn: &mut tokio_postgres::Client,
+    sql_conn: &tokio_postgres::Client,
     searched_symbol: &str,
     time_epoch: i64,
 ) -> Result<Vec<StockVal>, ReturnFlags> {
     /*
      * Returns all stock values from database since a time epoch.
      */
+    if !is_valid_symbol(searched_symbol) {
+        return Err(ReturnFlags::ServerDbSearchStockNotFound);
+    }
 
     // Query database for table.
     let mut stocks: Vec<StockVal> = Vec::new();
@@ -139,7 +152,7 @@ pub async fn get_stock_from_db_since_epoch(
 ///   };
 /// ```
 pub async fn get_stock_from_db_between_epochs(
-    sql_conn: &mut tokio_postgres::Client,
+    sql_conn: &tokio_postgres::Client,
     searched_symbol: &str,
     first_time_epoch: i64,
     second_time_epoch: i64,
@@ -147,6 +160,9 @@ pub async fn get_stock_from_db_between_epochs(
     /*
      * Returns all stock values from database between two time epochs.
      */
+    if !is_valid_symbol(searched_symbol) {
+        return Err(ReturnFlags::ServerDbSearchStockNotFound);
+    }
 
     // Query database for table.
     let mut stocks: Vec<StockVal> = Vec::new();
@@ -177,3 +193,109 @@ pub async fn get_stock_from_db_between_epochs(
         Err(_) => Err(ReturnFlags::ServerDbSearchStockNotFound),
     }
 }
+
+/// Returns the latest stock value from the postgres SQL database.
+///
+/// Takes in a stock symbol and returns the value with the highest time epoch.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// searched_symbol - The name of the stock table.
+///
+/// Returns: the latest ```StockVal``` on success, ```ReturnFlags::ServerDbSearchStockNotFound```
+/// if the stock does not exist or has no values.
+///
+/// Example:
+/// ```rust
+///    let quote = get_stock_from_db_latest(&sql_conn, "AAPL").await?;
+///    info!("AAPL asks {}", quote.ask_price);
+/// ```
+pub async fn get_stock_from_db_latest(
+    sql_conn: &tokio_postgres::Client,
+    searched_symbol: &str,
+) -> Result<StockVal, ReturnFlags> {
+    /*
+     * Returns the latest stock value from database.
+     */
+    if !is_valid_symbol(searched_symbol) {
+        return Err(ReturnFlags::ServerDbSearchStockNotFound);
+    }
+
+    let rows = sql_conn
+        .query(
+            format!(
+                "SELECT * FROM asset_schema.{} ORDER BY time_epoch DESC LIMIT 1",
+                searched_symbol
+            )
+            .as_str(),
+            &[],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbSearchStockNotFound)?;
+    let row = rows
+        .first()
+        .ok_or(ReturnFlags::ServerDbSearchStockNotFound)?;
+
+    let read = |_| ReturnFlags::ServerDbSearchStockNotFound;
+    Ok(StockVal {
+        id: row.try_get(0).map_err(read)?,
+        isin: row.try_get(1).map_err(read)?,
+        time_epoch: row.try_get(2).map_err(read)?,
+        ask_price: row.try_get(3).map_err(read)?,
+        bid_price: row.try_get(4).map_err(read)?,
+        volume: row.try_get(5).map_err(read)?,
+    })
+}
+
+/// Returns the stock values stored after another one, in the order they were stored.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// searched_symbol - The name of the stock table.
+/// id - The id of the last value already read, ```0``` for every value.
+///
+/// Returns: the values with a greater id on success,
+/// ```ReturnFlags::ServerDbSearchStockNotFound``` if the stock does not exist.
+///
+/// Example:
+/// ```rust
+///    for quote in get_stock_from_db_after_id(&sql_conn, "AAPL", last_id).await? { /* ... */ }
+/// ```
+pub async fn get_stock_from_db_after_id(
+    sql_conn: &tokio_postgres::Client,
+    searched_symbol: &str,
+    id: i64,
+) -> Result<Vec<StockVal>, ReturnFlags> {
+    if !is_valid_symbol(searched_symbol) {
+        return Err(ReturnFlags::ServerDbSearchStockNotFound);
+    }
+
+    let rows = sql_conn
+        .query(
+            format!(
+                "SELECT * FROM asset_schema.{} WHERE id > $1 ORDER BY id",
+                searched_symbol
+            )
+            .as_str(),
+            &[&id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbSearchStockNotFound)?;
+
+    let read = |_| ReturnFlags::ServerDbSearchStockNotFound;
+    rows.iter()
+        .map(|row| {
+            Ok(StockVal {
+                id: row.try_get(0).map_err(read)?,
+                isin: row.try_get(1).map_err(read)?,
+                time_epoch: row.try_get(2).map_err(read)?,
+                ask_price: row.try_get(3).map_err(read)?,
+                bid_price: row.try_get(4).map_err(read)?,
+                volume: row.try_get(5).map_err(read)?,
+            })
+        })
+        .collect()
+}
@@ -1,45 +1,35 @@
 use crate::common::misc::return_flags::ReturnFlags;
 
-use crate::server::db::cmd::user_exists::user_exists;
-
 pub async fn get_user_salt(
     sql_conn: &tokio_postgres::Client,
     username: &str,
     is_email: bool,
     is_server: bool,
 ) -> Result<String, ReturnFlags> {
-    /* check that user exists*/
-    if user_exists(sql_conn, username).await {
-        if is_server {
-            if is_email {
-                for row in
-                    &sql_conn.query("SELECT username, server_email_salt FROM accounts_schema.accounts WHERE username LIKE $1", 
-                                  &[&username]).await.unwrap() {
-                        return Ok(row.get(1));
-                    }
-            } else {
-                for row in
-                    &sql_conn.query("SELECT username, server_pass_salt FROM accounts_schema.accounts WHERE username LIKE $1", 
-                                  &[&username]).await.unwrap() {
-                        return Ok(row.get(1));
-                    }
-            }
-        } else {
-            if is_email {
-                for row in
-                    &sql_conn.query("SELECT username, client_email_salt FROM accounts_schema.accounts WHERE username LIKE $1", 
-                                  &[&username]).await.unwrap() {
-                        return Ok(row.get(1));
-                    }
-            } else {
-                for row in
-                    &sql_conn.query("SELECT username, client_pass_salt FROM accounts_schema.accounts WHERE username LIKE $1", 
-                                  &[&username]).await.unwrap() {
-                        return Ok(row.get(1));
-                    }
-            }
+    let query = match (is_server, is_email) {
+        (true, true) => {
+            "SELECT username, server_email_salt FROM accounts_schema.accounts WHERE username LIKE $1"
         }
-    }
+        (true, false) => {
+            "SELECT username, server_pass_salt FROM accounts_schema.accounts WHERE username LIKE $1"
+        }
+        (false, true) => {
+            "SELECT username, client_email_salt FROM accounts_schema.accounts WHERE username LIKE $1"
+        }
+        (false, false) => {
+            "SELECT username, client_pass_salt FROM accounts_schema.accounts WHERE username LIKE $1"
+        }
+    };
 
-    Err(ReturnFlags::ServerDbUserSaltNotFound)
+    /* unknown users have no rows */
+    let rows = sql_conn
+        .query(query, &[&username])
+        .await
+        .map_err(|_| ReturnFlags::ServerDbQueryFailed)?;
+    match rows.first() {
+        Some(row) => row
+            .try_get(1)
+            .map_err(|_| ReturnFlags::ServerDbUserSaltNotFound),
+        None => Err(ReturnFlags::ServerDbUserSaltNotFound),
+    }
 }
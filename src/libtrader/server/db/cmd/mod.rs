@@ -2,10 +2,23 @@ pub mod create_company;
 pub mod get_company;
 
 pub mod create_stock;
+pub mod create_stock_val;
 pub mod get_stock;
 
 pub mod create_position;
 pub mod create_transaction;
+pub mod get_open_positions;
+pub mod update_position;
+
+pub mod create_order;
+pub mod create_order_group;
+pub mod get_orders;
+pub mod update_order;
+
+pub mod apply_cash;
+pub mod get_cash_balance;
+pub mod get_dividends;
+pub mod update_dividend;
 
 pub mod get_user_hash;
 pub mod get_user_id;
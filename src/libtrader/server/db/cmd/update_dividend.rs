@@ -0,0 +1,28 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Marks a dividend as paid.
+///
+/// Should be used within the database transaction paying the dividend, see
+/// ```get_dividend_for_update()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// dividend_id - The id of the dividend.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+pub async fn set_dividend_paid<C: GenericClient>(
+    sql_conn: &C,
+    dividend_id: i64,
+) -> Result<(), ReturnFlags> {
+    sql_conn
+        .execute(
+            "UPDATE public.dividends SET is_paid = TRUE WHERE id = $1",
+            &[&dividend_id],
+        )
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)?;
+    Ok(())
+}
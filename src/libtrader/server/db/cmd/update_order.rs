@@ -0,0 +1,127 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::order::{Order, OrderStatus};
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Stores the progress of an order on the postgres SQL database.
+///
+/// Only the amount, fill and status columns are written. The amount changes when the exits of
+/// a bracket follow a partially filled entry.
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// order - The order, with its fill and status members updated.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     update_order(&db_transaction, &order).await?;
+/// ```
+pub async fn update_order<C: GenericClient>(
+    sql_conn: &C,
+    order: &Order,
+) -> Result<(), ReturnFlags> {
+    match sql_conn
+        .execute(
+            "UPDATE accounts_schema.orders
+                SET stock_filled = $2, is_filled = $3, status = $4, stock_amount = $5
+                WHERE id = $1",
+            &[
+                &order.id,
+                &order.stock_filled,
+                &order.is_filled,
+                &order.status.as_str(),
+                &order.stock_amount,
+            ],
+        )
+        .await
+    {
+        Ok(1) => Ok(()),
+        _ => Err(ReturnFlags::ServerDbWriteFailed),
+    }
+}
+
+/// Stores the trigger state of a stop order on the postgres SQL database.
+///
+/// Only the trigger columns are written, and only while the order is open. The trigger must be
+/// evaluated on the order as locked by ```get_order_for_update()```, so a concurrent amendment
+/// of the stop is never overwritten, see ```store_trigger()```.
+/// Should be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The database transaction to use.
+/// order - The order, with its trigger members updated.
+///
+/// Returns: whether the order was still open on success, ```ReturnFlags::ServerDbWriteFailed```
+/// on error.
+///
+/// Example:
+/// ```rust
+///     if trigger_order(&mut order, &quote) {
+///         update_order_trigger(&db_transaction, &order).await?;
+///     }
+/// ```
+pub async fn update_order_trigger<C: GenericClient>(
+    sql_conn: &C,
+    order: &Order,
+) -> Result<bool, ReturnFlags> {
+    sql_conn
+        .execute(
+            "UPDATE accounts_schema.orders
+                SET stop_price = $2, water_mark = $3, is_triggered = $4
+                WHERE id = $1 AND status = $5",
+            &[
+                &order.id,
+                &order.stop_price,
+                &order.water_mark,
+                &order.is_triggered,
+                &OrderStatus::Open.as_str(),
+            ],
+        )
+        .await
+        .map(|updated| updated == 1)
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)
+}
+
+/// Stores the amended terms of an order on the postgres SQL database.
+///
+/// Only the limit price, amount and stop price columns are written.
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// order - The order, with its terms amended.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     update_order_terms(&db_transaction, &order).await?;
+/// ```
+pub async fn update_order_terms<C: GenericClient>(
+    sql_conn: &C,
+    order: &Order,
+) -> Result<(), ReturnFlags> {
+    match sql_conn
+        .execute(
+            "UPDATE accounts_schema.orders
+                SET stock_price = $2, stock_amount = $3, stop_price = $4
+                WHERE id = $1",
+            &[
+                &order.id,
+                &order.stock_price,
+                &order.stock_amount,
+                &order.stop_price,
+            ],
+        )
+        .await
+    {
+        Ok(1) => Ok(()),
+        _ => Err(ReturnFlags::ServerDbWriteFailed),
+    }
+}
@@ -0,0 +1,48 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::position::Position;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// Stores the closing of a position on the postgres SQL database.
+///
+/// Only the closing columns of the position are written, the opening ones never change.
+/// Can be used within a database transaction, see ```tokio_postgres::GenericClient```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// position_id - The id of the position row.
+/// position - The position, with its closing members updated.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+///
+/// Example:
+/// ```rust
+///     update_position(&db_transaction, position_id, &position).await?;
+/// ```
+pub async fn update_position<C: GenericClient>(
+    sql_conn: &C,
+    position_id: i64,
+    position: &Position,
+) -> Result<(), ReturnFlags> {
+    match sql_conn
+        .execute(
+            "UPDATE portfolio_schema.positions
+                SET stock_close_amount = $2, stock_close_price = $3, stock_close_cost = $4,
+                    close_epoch = $5, is_open = $6
+                WHERE id = $1",
+            &[
+                &position_id,
+                &position.stock_close_amount,
+                &position.stock_close_price,
+                &position.stock_close_cost,
+                &position.close_epoch,
+                &position.is_open,
+            ],
+        )
+        .await
+    {
+        Ok(1) => Ok(()),
+        _ => Err(ReturnFlags::ServerDbWriteFailed),
+    }
+}
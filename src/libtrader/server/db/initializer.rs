@@ -1,3 +1,5 @@
+use crate::common::misc::return_flags::ReturnFlags;
+
 /// Establishes a postgresql connection to the SQL database.
 ///
 /// Creates a postgresql connection.
@@ -36,3 +38,31 @@ pub async fn db_connect(
     });
     Ok(client)
 }
+
+/// Establishes a postgresql connection with credentials taken from the environment.
+///
+/// Used for connections of their own, such as those running a database transaction.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// user_var - The environment variable holding the name of the user.
+/// pass_var - The environment variable holding the password of the user.
+///
+/// Returns: the ```tokio_postgres::Client``` on success, ```ReturnFlags::ServerDbConnectFailed```
+/// if a variable is missing or the connection failed.
+///
+/// Example:
+/// ```rust
+/// let mut trade_conn = db_connect_as("DB_ACC_USER", "DB_ACC_PASS").await?;
+/// ```
+pub async fn db_connect_as(
+    user_var: &str,
+    pass_var: &str,
+) -> Result<tokio_postgres::Client, ReturnFlags> {
+    match (std::env::var(user_var), std::env::var(pass_var)) {
+        (Ok(user), Ok(pass)) => db_connect(user, pass)
+            .await
+            .map_err(|_| ReturnFlags::ServerDbConnectFailed),
+        _ => Err(ReturnFlags::ServerDbConnectFailed),
+    }
+}
@@ -1,2 +1,4 @@
 pub mod cmd;
 pub mod initializer;
+#[cfg(test)]
+pub mod test_db;
@@ -0,0 +1,10 @@
+-- Trades write the transaction and the position in one database transaction,
+-- using the accounts user, and price the fill from the asset tables.
+GRANT USAGE ON SCHEMA portfolio_schema TO accounts_schema_usr;
+GRANT SELECT, INSERT, UPDATE ON ALL TABLES IN SCHEMA portfolio_schema TO accounts_schema_usr;
+GRANT USAGE ON ALL SEQUENCES IN SCHEMA portfolio_schema TO accounts_schema_usr;
+
+GRANT USAGE ON SCHEMA asset_schema TO accounts_schema_usr;
+GRANT SELECT ON ALL TABLES IN SCHEMA asset_schema TO accounts_schema_usr;
+ALTER DEFAULT PRIVILEGES IN SCHEMA asset_schema GRANT SELECT ON TABLES TO accounts_schema_usr;
+GRANT SELECT ON public.companies TO accounts_schema_usr;
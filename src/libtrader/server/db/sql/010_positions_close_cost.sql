@@ -0,0 +1,3 @@
+-- Proceeds of the shares of a position closed so far.
+ALTER TABLE portfolio_schema.positions
+	ADD COLUMN stock_close_cost DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Virtual cash of the accounts, in cents. Accounts created before the ledger
+-- start with no cash.
+ALTER TABLE accounts_schema.accounts
+	ADD COLUMN cash_balance BIGINT NOT NULL DEFAULT 0 CHECK (cash_balance >= 0);
+
+-- Every change of a cash balance, with the balance after the change.
+CREATE TABLE accounts_schema.cash_ledger (
+	id				BIGSERIAL PRIMARY KEY,
+	user_id			BIGINT NOT NULL,
+	amount			BIGINT NOT NULL,
+	reason			TEXT NOT NULL,
+	stock_symbol	TEXT NOT NULL,
+	balance			BIGINT NOT NULL,
+	time_epoch		BIGINT NOT NULL
+);
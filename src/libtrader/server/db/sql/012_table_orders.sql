@@ -0,0 +1,16 @@
+-- Limit orders, filled by the matching engine as quotes cross their price.
+-- Partial fills grow stock_filled until it reaches stock_amount.
+CREATE TABLE accounts_schema.orders (
+	id				BIGSERIAL PRIMARY KEY,
+	user_id			BIGINT NOT NULL,
+	stock_symbol	TEXT NOT NULL,
+	is_buy			BOOLEAN NOT NULL,
+	stock_price		DOUBLE PRECISION NOT NULL,
+	stock_amount	BIGINT NOT NULL,
+	stock_filled	BIGINT NOT NULL DEFAULT 0,
+	is_filled		BOOLEAN NOT NULL DEFAULT FALSE,
+	status			TEXT NOT NULL,
+	created_epoch	BIGINT NOT NULL
+);
+
+CREATE INDEX orders_open_symbol ON accounts_schema.orders (stock_symbol) WHERE status = 'open';
@@ -0,0 +1,8 @@
+-- Stop, stop-limit and trailing-stop orders wait for the market to cross their
+-- stop price. Trailing stops move the stop with the best price seen so far.
+ALTER TABLE accounts_schema.orders
+	ADD COLUMN order_type	TEXT NOT NULL DEFAULT 'limit',
+	ADD COLUMN stop_price	DOUBLE PRECISION NOT NULL DEFAULT 0,
+	ADD COLUMN trail_amount	DOUBLE PRECISION NOT NULL DEFAULT 0,
+	ADD COLUMN water_mark	DOUBLE PRECISION NOT NULL DEFAULT 0,
+	ADD COLUMN is_triggered	BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,18 @@
+-- One-cancels-other and bracket orders. The orders of a group point to it, and
+-- the positions opened by an order point to the order, so the exits of a
+-- bracket close the positions of its entry.
+CREATE TABLE accounts_schema.order_groups (
+	id				BIGSERIAL PRIMARY KEY,
+	user_id			BIGINT NOT NULL,
+	group_type		TEXT NOT NULL,
+	created_epoch	BIGINT NOT NULL
+);
+
+ALTER TABLE accounts_schema.orders
+	ADD COLUMN group_id		BIGINT NOT NULL DEFAULT 0,
+	ADD COLUMN group_role	TEXT NOT NULL DEFAULT 'single';
+
+CREATE INDEX orders_group ON accounts_schema.orders (group_id) WHERE group_id <> 0;
+
+ALTER TABLE portfolio_schema.positions
+	ADD COLUMN order_id		BIGINT NOT NULL DEFAULT 0;
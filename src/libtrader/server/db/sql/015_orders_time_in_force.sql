@@ -0,0 +1,8 @@
+-- Time in force of the orders. Orders with an expire_epoch are expired by the
+-- server once it has passed, expired orders are kept with their final state.
+ALTER TABLE accounts_schema.orders
+	ADD COLUMN time_in_force	TEXT NOT NULL DEFAULT 'gtc',
+	ADD COLUMN expire_epoch		BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX orders_expiring ON accounts_schema.orders (expire_epoch)
+	WHERE expire_epoch <> 0 AND status IN ('open', 'pending');
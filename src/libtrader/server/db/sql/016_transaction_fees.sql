@@ -0,0 +1,4 @@
+-- Fees charged on the trades are stored as transactions of their own, next to
+-- the trade they were charged on. Transactions stored before are trades.
+ALTER TABLE accounts_schema.transactions
+	ADD COLUMN kind	TEXT NOT NULL DEFAULT 'trade';
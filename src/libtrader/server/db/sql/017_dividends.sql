@@ -0,0 +1,14 @@
+-- Cash dividends of the listed stocks, stored by the market data importer. The
+-- server pays every dividend once its pay_epoch has passed, crediting each
+-- open lot of the stock, and marks it paid in the same database transaction.
+CREATE TABLE public.dividends (
+	id					BIGSERIAL PRIMARY KEY,
+	stock_symbol		TEXT NOT NULL,
+	cents_per_share		BIGINT NOT NULL CHECK (cents_per_share > 0),
+	pay_epoch			BIGINT NOT NULL,
+	is_paid				BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX dividends_unpaid ON public.dividends (pay_epoch) WHERE NOT is_paid;
+
+GRANT SELECT, UPDATE ON public.dividends TO accounts_schema_usr;
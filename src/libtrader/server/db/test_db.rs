@@ -0,0 +1,108 @@
+use data_encoding::HEXUPPER;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+
+use crate::server::db::initializer::db_connect;
+use crate::server::engine::engine_conn::connect_trade_conn;
+
+/// Returns a connection to the test database as its owner.
+///
+/// The database is configured as for the server, see ```scripts/env.sh```, with the tables of
+/// ```server/db/sql``` created. Tests needing a database are skipped when ```DB_HOST``` is not
+/// set.
+///
+/// Returns: the connection, ```None``` if no database is configured.
+///
+/// Example:
+/// ```rust
+///     let admin_conn = match test_db_admin().await {
+///         Some(conn) => conn,
+///         None => return,
+///     };
+/// ```
+pub async fn test_db_admin() -> Option<tokio_postgres::Client> {
+    std::env::var("DB_HOST").ok()?;
+    let user = std::env::var("DB_USER").expect("DB_USER not set");
+    let pass = std::env::var("DB_PASS").expect("DB_PASS not set");
+    Some(
+        db_connect(user, pass)
+            .await
+            .expect("failed connecting to the test database"),
+    )
+}
+
+/// Returns a connection to the test database as the accounts user, as the server uses.
+pub async fn test_db_trade_conn() -> tokio_postgres::Client {
+    connect_trade_conn()
+        .await
+        .expect("failed connecting to the test database")
+}
+
+/// Returns a random string of ```len``` upper case hexadecimal digits.
+fn random_tag(len: usize) -> String {
+    let mut bytes = vec![0u8; len / 2];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("failed generating a random tag");
+    HEXUPPER.encode(&bytes)
+}
+
+/// Lists a company under a new symbol and creates its stock table.
+///
+/// Returns: the symbol of the company.
+pub async fn test_stock(admin_conn: &tokio_postgres::Client) -> String {
+    let symbol = format!("T{}", random_tag(10));
+    admin_conn
+        .execute(
+            "INSERT INTO public.companies
+                (symbol, isin, company_name, primary_exchange, sector, industry,
+                primary_sic_code, employees)
+                VALUES ($1, $1, $1, 'TEST', 'TEST', 'TEST', '0000', 1)",
+            &[&symbol],
+        )
+        .await
+        .expect("failed creating the test company");
+    admin_conn
+        .execute(
+            format!(
+                "CREATE TABLE asset_schema.{} (
+                    id          BIGSERIAL PRIMARY KEY,
+                    isin        TEXT NOT NULL,
+                    time_epoch  BIGINT NOT NULL,
+                    ask_price   DOUBLE PRECISION NOT NULL,
+                    bid_price   DOUBLE PRECISION NOT NULL,
+                    volume      BIGINT NOT NULL
+                )",
+                symbol
+            )
+            .as_str(),
+            &[],
+        )
+        .await
+        .expect("failed creating the test stock table");
+    symbol
+}
+
+/// Creates an account with a cash balance.
+///
+/// Arguments:
+/// admin_conn - The connection to the test database as its owner.
+/// cash_balance - The cash balance of the account, in cents.
+///
+/// Returns: the ID of the account.
+pub async fn test_account(admin_conn: &tokio_postgres::Client, cash_balance: i64) -> i64 {
+    let tag = random_tag(16);
+    admin_conn
+        .query_one(
+            "INSERT INTO accounts_schema.accounts
+                (username, email_hash, server_email_salt, client_email_salt, pass_hash,
+                server_pass_salt, client_pass_salt, cash_balance)
+                VALUES ($1, 'e' || $1, 'se' || $1, 'ce' || $1, 'p' || $1, 'sp' || $1,
+                'cp' || $1, $2)
+                RETURNING id",
+            &[&tag, &cash_balance],
+        )
+        .await
+        .expect("failed creating the test account")
+        .get(0)
+}
@@ -16,19 +16,21 @@ pub struct Account {
     pub is_pass: bool,
     pub portfolio: Portfolio,
     pub transactions: Vec<Transaction>,
+    pub cash_balance: i64, // in cents
 }
 
 impl std::fmt::Display for Account {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {}, {}, {}, {:#?})",
+            "({}, {}, {}, {}, {}, {:#?}, {})",
             self.username,
             self.email_hash,
             self.is_pass,
             self.pass_hash,
             self.portfolio,
-            self.transactions
+            self.transactions,
+            self.cash_balance
         )
     }
 }
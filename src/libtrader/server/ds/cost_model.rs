@@ -0,0 +1,199 @@
+use crate::common::account::transaction::TransactionKind;
+
+/// Slippage of the simulated fills, moving their price against the account.
+///
+/// Variants:
+/// None - Fills are made at the quoted price.
+/// Fixed - Fills are moved by a fixed number of basis points.
+/// Volume - Fills are moved by up to the number of basis points, in proportion to the share of
+/// the volume of the quote they take.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum Slippage {
+    #[default]
+    None,
+    Fixed(f64),
+    Volume(f64),
+}
+
+/// Costs of trading charged on every fill.
+///
+/// The default model is frictionless.
+///
+/// Members:
+/// flat_fee - The fee charged per trade, in cents.
+/// share_fee - The fee charged per share traded, in cents.
+/// commission - The commission charged on the value traded, as a fraction of it.
+/// slippage - The slippage of the fill prices.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CostModel {
+    pub flat_fee: i64,
+    pub share_fee: f64,
+    pub commission: f64,
+    pub slippage: Slippage,
+}
+
+impl CostModel {
+    /// Returns the fees charged on a fill.
+    ///
+    /// Arguments:
+    /// quantity - The number of shares filled.
+    /// price - The price of one share.
+    ///
+    /// Returns: the kind and amount in cents of every fee charged, fees of zero cents are left
+    /// out.
+    ///
+    /// Example:
+    /// ```rust
+    ///     let fees: i64 = cost_model.fees(10, 150.0).iter().map(|(_, fee)| fee).sum();
+    /// ```
+    pub fn fees(&self, quantity: i64, price: f64) -> Vec<(TransactionKind, i64)> {
+        let value = price * quantity as f64 * 100.0;
+        [
+            (TransactionKind::FlatFee, self.flat_fee),
+            (
+                TransactionKind::ShareFee,
+                (self.share_fee * quantity as f64).round() as i64,
+            ),
+            (
+                TransactionKind::Commission,
+                (self.commission * value).round() as i64,
+            ),
+        ]
+        .iter()
+        .copied()
+        .filter(|(_, fee)| *fee > 0)
+        .collect()
+    }
+
+    /// Returns the price of a fill once slipped.
+    ///
+    /// Purchases are filled higher and sales lower than quoted.
+    ///
+    /// Arguments:
+    /// is_buy - Whether the shares are bought or sold.
+    /// price - The quoted price of one share.
+    /// quantity - The number of shares filled.
+    /// volume - The volume of the quote, the whole slippage applies if it is not positive.
+    ///
+    /// Returns: the price of one share.
+    ///
+    /// Example:
+    /// ```rust
+    ///     let price = cost_model.fill_price(true, quote.ask_price, 10, quote.volume);
+    /// ```
+    pub fn fill_price(&self, is_buy: bool, price: f64, quantity: i64, volume: i64) -> f64 {
+        let bps = match self.slippage {
+            Slippage::None => 0.0,
+            Slippage::Fixed(bps) => bps,
+            Slippage::Volume(bps) if volume > 0 => bps * (quantity as f64 / volume as f64).min(1.0),
+            Slippage::Volume(bps) => bps,
+        };
+        if is_buy {
+            price * (1.0 + bps / 10_000.0)
+        } else {
+            price * (1.0 - bps / 10_000.0)
+        }
+    }
+
+    /// Returns whether every fee and the slippage are finite and not negative.
+    pub fn is_valid(&self) -> bool {
+        let valid = |rate: f64| rate.is_finite() && rate >= 0.0;
+        let bps = match self.slippage {
+            Slippage::None => 0.0,
+            Slippage::Fixed(bps) | Slippage::Volume(bps) => bps,
+        };
+        self.flat_fee >= 0 && valid(self.share_fee) && valid(self.commission) && valid(bps)
+    }
+}
+
+/// Parses a slippage model out of ```none```, ```fixed:BPS``` or ```volume:BPS```.
+///
+/// Arguments:
+/// slippage - The model to parse.
+///
+/// Returns: the ```Slippage```, ```None``` if the model is invalid.
+///
+/// Example:
+/// ```rust
+///     assert_eq!(parse_slippage("fixed:5"), Some(Slippage::Fixed(5.0)));
+/// ```
+pub fn parse_slippage(slippage: &str) -> Option<Slippage> {
+    if slippage == "none" {
+        return Some(Slippage::None);
+    }
+    let (model, bps) = slippage.split_once(':')?;
+    let bps: f64 = bps.parse().ok()?;
+    if !bps.is_finite() || bps < 0.0 {
+        return None;
+    }
+    match model {
+        "fixed" => Some(Slippage::Fixed(bps)),
+        "volume" => Some(Slippage::Volume(bps)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fees() {
+        assert!(CostModel::default().fees(10, 150.0).is_empty());
+
+        let cost_model = CostModel {
+            flat_fee: 100,
+            share_fee: 0.5,
+            commission: 0.001,
+            ..CostModel::default()
+        };
+        assert_eq!(
+            cost_model.fees(10, 150.0),
+            vec![
+                (TransactionKind::FlatFee, 100),
+                (TransactionKind::ShareFee, 5),
+                (TransactionKind::Commission, 150),
+            ]
+        );
+        /* fees rounding to zero are not charged */
+        let cost_model = CostModel {
+            flat_fee: 0,
+            ..cost_model
+        };
+        assert_eq!(
+            cost_model.fees(1, 1.0),
+            vec![(TransactionKind::ShareFee, 1)]
+        );
+    }
+
+    #[test]
+    fn test_fill_price() {
+        assert_eq!(CostModel::default().fill_price(true, 100.0, 10, 100), 100.0);
+
+        let fixed = CostModel {
+            slippage: Slippage::Fixed(50.0),
+            ..CostModel::default()
+        };
+        assert!((fixed.fill_price(true, 100.0, 10, 100) - 100.5).abs() < 1e-9);
+        assert!((fixed.fill_price(false, 100.0, 10, 100) - 99.5).abs() < 1e-9);
+
+        /* taking a tenth of the volume slips a tenth of the basis points */
+        let volume = CostModel {
+            slippage: Slippage::Volume(50.0),
+            ..CostModel::default()
+        };
+        assert!((volume.fill_price(true, 100.0, 10, 100) - 100.05).abs() < 1e-9);
+        assert!((volume.fill_price(false, 100.0, 500, 100) - 99.5).abs() < 1e-9);
+        assert!((volume.fill_price(true, 100.0, 10, 0) - 100.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_slippage() {
+        assert_eq!(parse_slippage("none"), Some(Slippage::None));
+        assert_eq!(parse_slippage("fixed:5"), Some(Slippage::Fixed(5.0)));
+        assert_eq!(parse_slippage("volume:12.5"), Some(Slippage::Volume(12.5)));
+        assert_eq!(parse_slippage("fixed:-1"), None);
+        assert_eq!(parse_slippage("spread:5"), None);
+        assert_eq!(parse_slippage("fixed"), None);
+    }
+}
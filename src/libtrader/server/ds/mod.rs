@@ -1,2 +1,5 @@
 pub mod account;
+pub mod cost_model;
 pub mod global_state;
+pub mod quote_feed;
+pub mod trading_config;
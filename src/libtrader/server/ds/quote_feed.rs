@@ -0,0 +1,52 @@
+use tokio::sync::broadcast;
+
+use crate::common::generic::stock_val::StockVal;
+
+/// Number of quotes kept for connections that fall behind the feed.
+pub static QUOTE_FEED_CAPACITY: usize = 1024;
+
+/// Feed of newly stored stock values, shared by all connections.
+///
+/// Every connection holds a receiver and pushes the quotes matching its subscriptions. Connections
+/// falling more than ```QUOTE_FEED_CAPACITY``` quotes behind skip the oldest ones.
+///
+/// Example:
+/// ```rust
+///     let quote_feed = QuoteFeed::new();
+///     let mut quotes = quote_feed.subscribe();
+///     quote_feed.publish("AAPL", stock_val);
+///     let (symbol, stock_val) = quotes.recv().await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuoteFeed {
+    sender: broadcast::Sender<(String, StockVal)>,
+}
+
+impl QuoteFeed {
+    /// Creates an empty feed.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(QUOTE_FEED_CAPACITY);
+        QuoteFeed { sender }
+    }
+
+    /// Publishes a new stock value to every receiver.
+    ///
+    /// Arguments:
+    /// symbol - The symbol of the stock.
+    /// stock_val - The new value.
+    pub fn publish(&self, symbol: &str, stock_val: StockVal) {
+        /* no connection listening is not an error */
+        let _ = self.sender.send((symbol.to_string(), stock_val));
+    }
+
+    /// Returns a receiver of the values published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<(String, StockVal)> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for QuoteFeed {
+    fn default() -> Self {
+        QuoteFeed::new()
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::OnceLock;
+
+use crate::server::ds::cost_model::CostModel;
+
+/// Starting cash balance of new accounts when not configured, in cents.
+pub static DEFAULT_STARTING_CASH: i64 = 100_000 * 100;
+
+/// Close of the exchange session when not configured, in seconds after midnight UTC.
+pub static DEFAULT_SESSION_CLOSE: i64 = 21 * 3600;
+
+static SECONDS_PER_DAY: i64 = 24 * 3600;
+
+static TRADING_CONFIG: OnceLock<TradingConfig> = OnceLock::new();
+
+/// Server wide settings of the simulated market.
+///
+/// Set once at server start, from the server options, with ```set_trading_config()```.
+///
+/// Members:
+/// starting_cash - The cash balance of new accounts, in cents.
+/// session_close - The close of the exchange sessions, in seconds after midnight UTC. Sessions
+/// run from Monday to Friday.
+/// cost_model - The fees and slippage charged on every fill.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TradingConfig {
+    pub starting_cash: i64,
+    pub session_close: i64,
+    pub cost_model: CostModel,
+}
+
+impl Default for TradingConfig {
+    fn default() -> Self {
+        TradingConfig {
+            starting_cash: DEFAULT_STARTING_CASH,
+            session_close: DEFAULT_SESSION_CLOSE,
+            cost_model: CostModel::default(),
+        }
+    }
+}
+
+impl TradingConfig {
+    /// Returns the close of the exchange session open at ```time_epoch```.
+    ///
+    /// Outside of a session, the close of the next session is returned.
+    ///
+    /// Arguments:
+    /// time_epoch - A unix epoch.
+    ///
+    /// Returns: the unix epoch of the first session close after ```time_epoch```.
+    pub fn session_close_after(&self, time_epoch: i64) -> i64 {
+        let mut day = time_epoch.div_euclid(SECONDS_PER_DAY);
+        if time_epoch.rem_euclid(SECONDS_PER_DAY) >= self.session_close {
+            day += 1;
+        }
+        /* the epoch starts on a Thursday, skip Saturdays and Sundays */
+        while (day + 3).rem_euclid(7) >= 5 {
+            day += 1;
+        }
+        day * SECONDS_PER_DAY + self.session_close
+    }
+}
+
+/// Parses the close of the exchange sessions out of a ```HH:MM``` UTC time.
+///
+/// Arguments:
+/// session_close - The time to parse.
+///
+/// Returns: the close in seconds after midnight UTC, ```None``` if the time is invalid.
+///
+/// Example:
+/// ```rust
+///     assert_eq!(parse_session_close("21:00"), Some(21 * 3600));
+/// ```
+pub fn parse_session_close(session_close: &str) -> Option<i64> {
+    let (hours, minutes) = session_close.split_once(':')?;
+    let hours: i64 = hours.parse().ok()?;
+    let minutes: i64 = minutes.parse().ok()?;
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    Some(hours * 3600 + minutes * 60)
+}
+
+/// Sets the trading configuration of the server.
+///
+/// Arguments:
+/// config - The configuration to use.
+///
+/// Returns: nothing on success, the configuration back if one was already set.
+///
+/// Example:
+/// ```rust
+///     set_trading_config(TradingConfig { starting_cash: 50_000 * 100, ..TradingConfig::default() })?;
+/// ```
+pub fn set_trading_config(config: TradingConfig) -> Result<(), TradingConfig> {
+    TRADING_CONFIG.set(config)
+}
+
+/// Returns the trading configuration of the server, the default one if none was set.
+pub fn trading_config() -> &'static TradingConfig {
+    TRADING_CONFIG.get_or_init(TradingConfig::default)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_trading_config_set_once() {
+        /* the first configuration wins, later ones are handed back */
+        let config = trading_config().clone();
+        let other = TradingConfig {
+            starting_cash: config.starting_cash + 1,
+            ..config.clone()
+        };
+        assert_eq!(set_trading_config(other.clone()), Err(other));
+        assert_eq!(trading_config(), &config);
+    }
+
+    #[test]
+    fn test_session_close_after() {
+        let config = TradingConfig {
+            session_close: 21 * 3600,
+            ..TradingConfig::default()
+        };
+        /* Monday 2024-01-08 00:00 UTC */
+        let monday = 1_704_672_000;
+
+        assert_eq!(config.session_close_after(monday), monday + 21 * 3600);
+        assert_eq!(
+            config.session_close_after(monday + 21 * 3600 - 1),
+            monday + 21 * 3600
+        );
+        /* after the close, the next session closes */
+        assert_eq!(
+            config.session_close_after(monday + 21 * 3600),
+            monday + 24 * 3600 + 21 * 3600
+        );
+        /* Friday evening and the weekend wait for Monday */
+        let next_monday = monday + 7 * 24 * 3600;
+        assert_eq!(
+            config.session_close_after(monday + 4 * 24 * 3600 + 22 * 3600),
+            next_monday + 21 * 3600
+        );
+        assert_eq!(
+            config.session_close_after(monday + 6 * 24 * 3600),
+            next_monday + 21 * 3600
+        );
+    }
+
+    #[test]
+    fn test_parse_session_close() {
+        assert_eq!(parse_session_close("21:00"), Some(21 * 3600));
+        assert_eq!(parse_session_close("09:30"), Some(9 * 3600 + 30 * 60));
+        assert_eq!(parse_session_close("24:00"), None);
+        assert_eq!(parse_session_close("21"), None);
+        assert_eq!(parse_session_close("ab:cd"), None);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::common::account::order::{Order, OrderStatus};
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_orders::get_order_for_update;
+use crate::server::db::cmd::update_order::update_order;
+use crate::server::engine::engine_conn::{begin_transaction, commit_transaction};
+use crate::server::engine::resolve_group::resolve_group;
+
+/// Closes an order before it is completely filled.
+///
+/// The order keeps its fills and is stored with its final ```status```, along with the
+/// changes to the other orders of its group, see ```resolve_group()```. Orders already in a
+/// final state are left as they are.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own, no other query may run on it meanwhile.
+/// order_id - The id of the order to close.
+/// status - The final state of the order, e.g. ```OrderStatus::Expired```.
+///
+/// Returns: the order as stored on success, ```None``` if no order has the id,
+/// ```ReturnFlags``` on error.
+///
+/// Example:
+/// ```rust
+///     let order = close_order(&mut trade_conn, order_id, OrderStatus::Expired).await?;
+/// ```
+pub async fn close_order(
+    trade_conn: &mut tokio_postgres::Client,
+    order_id: i64,
+    status: OrderStatus,
+) -> Result<Option<Order>, ReturnFlags> {
+    let db_transaction = begin_transaction(trade_conn).await?;
+    let mut order = match get_order_for_update(&db_transaction, order_id).await? {
+        Some((_, order)) => order,
+        None => return Ok(None),
+    };
+    if order.status.is_final() {
+        return Ok(Some(order));
+    }
+
+    order.status = status;
+    update_order(&db_transaction, &order).await?;
+    resolve_group(&db_transaction, &order, false).await?;
+    commit_transaction(db_transaction).await?;
+    Ok(Some(order))
+}
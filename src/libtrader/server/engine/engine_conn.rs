@@ -0,0 +1,101 @@
+use log::warn;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::initializer::db_connect_as;
+
+/// Opens a SQL connection of its own, to run database transactions on.
+///
+/// The shared SQL connection is used concurrently by the requests of every client, a database
+/// transaction on it would take in their queries. Requests writing in a database transaction
+/// open a connection of their own instead, background tasks keep theirs, see
+/// ```engine_conn()```.
+/// Should be used in Async contexts.
+///
+/// Returns: the connection on success, ```ReturnFlags``` on error.
+///
+/// Example:
+/// ```rust
+///     let mut trade_conn = connect_trade_conn().await?;
+/// ```
+pub async fn connect_trade_conn() -> Result<tokio_postgres::Client, ReturnFlags> {
+    db_connect_as("DB_ACC_USER", "DB_ACC_PASS").await
+}
+
+/// Returns the SQL connection of a background task, connecting again if it was lost.
+///
+/// Background tasks run database transactions, so they hold a connection of their own
+/// instead of the shared one.
+/// Should be used in Async contexts, with ```IP``` in scope.
+///
+/// Arguments:
+/// trade_conn - The connection of the task, ```None``` until it is connected.
+///
+/// Returns: the connection, ```None``` if connecting failed. The next call tries again.
+///
+/// Example:
+/// ```rust
+///     if let Some(conn) = engine_conn(&mut trade_conn).await { /* ... */ }
+/// ```
+pub async fn engine_conn(
+    trade_conn: &mut Option<tokio_postgres::Client>,
+) -> Option<&mut tokio_postgres::Client> {
+    let connected = match trade_conn {
+        Some(conn) => !conn.is_closed(),
+        None => false,
+    };
+    if !connected {
+        *trade_conn = match connect_trade_conn().await {
+            Ok(conn) => Some(conn),
+            Err(err) => {
+                warn!("ENGINE_CONNECT_FAILED: {}", err);
+                None
+            }
+        };
+    }
+    trade_conn.as_mut()
+}
+
+/// Starts a database transaction on a connection of its own.
+///
+/// Dropping the database transaction before ```commit_transaction()``` rolls it back, so
+/// returning early on an error undoes every write made in it.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own, see ```connect_trade_conn()```.
+///
+/// Returns: the database transaction on success, ```ReturnFlags::ServerDbWriteFailed``` on
+/// error.
+///
+/// Example:
+/// ```rust
+///     let db_transaction = begin_transaction(&mut trade_conn).await?;
+///     execute_buy(&db_transaction, user_id, &fill).await?;
+///     commit_transaction(db_transaction).await?;
+/// ```
+pub async fn begin_transaction(
+    trade_conn: &mut tokio_postgres::Client,
+) -> Result<tokio_postgres::Transaction<'_>, ReturnFlags> {
+    trade_conn
+        .transaction()
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)
+}
+
+/// Commits a database transaction started by ```begin_transaction()```.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// db_transaction - The database transaction to commit.
+///
+/// Returns: nothing on success, ```ReturnFlags::ServerDbWriteFailed``` on error.
+pub async fn commit_transaction(
+    db_transaction: tokio_postgres::Transaction<'_>,
+) -> Result<(), ReturnFlags> {
+    db_transaction
+        .commit()
+        .await
+        .map_err(|_| ReturnFlags::ServerDbWriteFailed)
+}
@@ -0,0 +1,52 @@
+use log::{info, warn};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::common::account::order::OrderStatus;
+
+use crate::server::db::cmd::get_orders::get_expired_orders;
+use crate::server::engine::close_order::close_order;
+use crate::server::engine::engine_conn::engine_conn;
+
+/// Time between two searches of expired orders.
+pub static ORDER_EXPIRY_PERIOD: Duration = Duration::from_secs(30);
+
+/// Expires the orders whose time in force ran out, every ```ORDER_EXPIRY_PERIOD```.
+///
+/// Expired orders keep their fills and stay stored with ```OrderStatus::Expired```. Runs
+/// forever, on a SQL connection of its own.
+/// Should be used in Async contexts, with ```IP``` in scope.
+///
+/// Example:
+/// ```rust
+///     tokio::spawn(IP.scope(local_addr, run_order_expiry()));
+/// ```
+pub async fn run_order_expiry() {
+    let mut ticks = tokio::time::interval(ORDER_EXPIRY_PERIOD);
+    let mut trade_conn: Option<tokio_postgres::Client> = None;
+
+    loop {
+        ticks.tick().await;
+        let conn = match engine_conn(&mut trade_conn).await {
+            Some(conn) => conn,
+            None => continue,
+        };
+
+        let time_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs() as i64)
+            .unwrap_or(0);
+        let expired = match get_expired_orders(&*conn, time_epoch).await {
+            Ok(expired) => expired,
+            Err(err) => {
+                warn!("ORDER_EXPIRY_FAILED: {}", err);
+                continue;
+            }
+        };
+        for order_id in expired {
+            match close_order(conn, order_id, OrderStatus::Expired).await {
+                Ok(_) => info!("ORDER_EXPIRED: {}", order_id),
+                Err(err) => warn!("ORDER_EXPIRY_FAILED: {} {}", order_id, err),
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use log::warn;
+
+use crate::common::account::fill::Fill;
+use crate::common::account::order::{GroupRole, Order, OrderStatus};
+use crate::common::generic::stock_val::StockVal;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::account::execution::{execute_buy, execute_sell};
+use crate::server::db::cmd::get_orders::{get_group_orders_for_update, get_order_for_update};
+use crate::server::db::cmd::update_order::update_order;
+use crate::server::ds::trading_config::trading_config;
+use crate::server::engine::engine_conn::{begin_transaction, commit_transaction};
+use crate::server::engine::match_order::match_order;
+use crate::server::engine::resolve_group::resolve_group;
+
+/// Fills a limit order against a quote, as far as the quote allows.
+///
+/// The order is read again and locked, so an order is never filled twice by concurrent
+/// matches. The fill is slipped by the configured ```CostModel```, but never past the limit
+/// of the order. It is executed like a market order, see ```execute_buy()``` and
+/// ```execute_sell()```, and the progress of the order is stored in the same database
+/// transaction, along with the changes to the other orders of its group, see
+/// ```resolve_group()```. An order the account can no longer pay or deliver for is rejected,
+/// its earlier partial fills are kept.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own, no other query may run on it meanwhile.
+/// order_id - The id of the order to fill.
+/// quote - The latest value of the stock.
+/// volume_left - The number of shares the quote can still fill.
+/// time_epoch - The unix epoch of the fill.
+///
+/// Returns: the order as stored and its ```Fill```, if any, on success,
+/// ```ReturnFlags::ServerPlaceOrderFailed``` if the order does not exist, ```ReturnFlags``` on
+/// other errors.
+///
+/// Example:
+/// ```rust
+///     let (order, fill) = fill_order(&mut trade_conn, order_id, &quote, quote.volume, now).await?;
+/// ```
+pub async fn fill_order(
+    trade_conn: &mut tokio_postgres::Client,
+    order_id: i64,
+    quote: &StockVal,
+    volume_left: i64,
+    time_epoch: i64,
+) -> Result<(Order, Option<Fill>), ReturnFlags> {
+    let db_transaction = begin_transaction(trade_conn).await?;
+    let (user_id, mut order) = get_order_for_update(&db_transaction, order_id)
+        .await?
+        .ok_or(ReturnFlags::ServerPlaceOrderFailed)?;
+    let (quantity, price) = match match_order(&order, quote, volume_left) {
+        Some(matched) => matched,
+        None => return Ok((order, None)),
+    };
+
+    /* slippage never fills an order past its limit */
+    let price = trading_config()
+        .cost_model
+        .fill_price(order.is_buy, price, quantity, quote.volume);
+    let price = match (order.order_type.has_limit(), order.is_buy) {
+        (true, true) => price.min(order.stock_price),
+        (true, false) => price.max(order.stock_price),
+        (false, _) => price,
+    };
+
+    let mut fill = Fill {
+        stock_symbol: order.stock_symbol.clone(),
+        quantity,
+        price,
+        time_epoch,
+        realized_pnl: 0.0,
+        order_id: order.id,
+    };
+    let executed = if order.is_buy {
+        execute_buy(&db_transaction, user_id, &fill).await
+    } else {
+        /* the exits of a bracket close the positions of its entry */
+        let entry_id = match order.group_role {
+            GroupRole::Leg => get_group_orders_for_update(&db_transaction, order.group_id)
+                .await?
+                .into_iter()
+                .find(|(_, other)| other.group_role == GroupRole::Entry)
+                .map(|(_, entry)| entry.id),
+            _ => None,
+        };
+        execute_sell(&db_transaction, user_id, &mut fill, entry_id).await
+    };
+
+    match executed {
+        Ok(()) => {
+            order.stock_filled += quantity;
+            order.is_filled = order.stock_filled >= order.stock_amount;
+            if order.is_filled {
+                order.status = OrderStatus::Filled;
+            }
+            update_order(&db_transaction, &order).await?;
+            resolve_group(&db_transaction, &order, true).await?;
+            commit_transaction(db_transaction).await?;
+            Ok((order, Some(fill)))
+        }
+        Err(err @ ReturnFlags::ServerAccInsufficientCash)
+        | Err(err @ ReturnFlags::ServerSellAssetOversold) => {
+            db_transaction
+                .rollback()
+                .await
+                .map_err(|_| ReturnFlags::ServerDbWriteFailed)?;
+            warn!("FILL_ORDER_REJECTED: {} {}", order.id, err);
+            order.status = OrderStatus::Rejected;
+            let db_transaction = begin_transaction(trade_conn).await?;
+            update_order(&db_transaction, &order).await?;
+            resolve_group(&db_transaction, &order, false).await?;
+            commit_transaction(db_transaction).await?;
+            Ok((order, None))
+        }
+        Err(err) => Err(err),
+    }
+}
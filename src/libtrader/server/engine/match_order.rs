@@ -0,0 +1,174 @@
+use std::cmp::Ordering;
+
+use crate::common::account::order::{Order, OrderStatus};
+use crate::common::generic::stock_val::StockVal;
+
+/// Matches an order against a quote.
+///
+/// A buy order is filled at the ask when the ask is at or below its limit, a sell order at
+/// the bid when the bid is at or above its limit. Stop orders are only filled once triggered,
+/// see ```trigger_order()```, and those without a limit at any price. At most
+/// ```volume_left``` shares are filled, the rest of the order keeps waiting for the next
+/// quotes.
+///
+/// Arguments:
+/// order - The order to match.
+/// quote - The latest value of the stock.
+/// volume_left - The number of shares the quote can still fill.
+///
+/// Returns: the number of shares filled and their price, ```None``` if the order is not open
+/// or not triggered, the quote does not cross its limit, or no volume is left.
+///
+/// Example:
+/// ```rust
+///     if let Some((quantity, price)) = match_order(&order, &quote, quote.volume) {
+///         info!("{} shares at {}", quantity, price);
+///     }
+/// ```
+pub fn match_order(order: &Order, quote: &StockVal, volume_left: i64) -> Option<(i64, f64)> {
+    if order.status != OrderStatus::Open || (order.order_type.is_stop() && !order.is_triggered) {
+        return None;
+    }
+    let quantity = (order.stock_amount - order.stock_filled).min(volume_left);
+    let price = if order.is_buy {
+        quote.ask_price
+    } else {
+        quote.bid_price
+    };
+    if quantity <= 0 || !price.is_finite() || price <= 0.0 {
+        return None;
+    }
+
+    let crossed = if !order.order_type.has_limit() {
+        true
+    } else if order.is_buy {
+        price <= order.stock_price
+    } else {
+        price >= order.stock_price
+    };
+    if crossed {
+        Some((quantity, price))
+    } else {
+        None
+    }
+}
+
+/// Orders two orders on the same side of a stock by price priority.
+///
+/// Orders filled at the market price come first, then the highest buy and the lowest sell.
+/// Sorting with a stable sort keeps time priority between orders at the same price.
+///
+/// Example:
+/// ```rust
+///     buy_orders.sort_by(order_priority);
+/// ```
+pub fn order_priority(a: &Order, b: &Order) -> Ordering {
+    let by_limit = a.order_type.has_limit().cmp(&b.order_type.has_limit());
+    if by_limit != Ordering::Equal {
+        return by_limit;
+    }
+    let by_price = if a.is_buy {
+        b.stock_price.partial_cmp(&a.stock_price)
+    } else {
+        a.stock_price.partial_cmp(&b.stock_price)
+    };
+    by_price.unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::account::order::OrderType;
+
+    fn order(is_buy: bool, stock_price: f64, stock_amount: i64, created_epoch: i64) -> Order {
+        Order {
+            is_buy,
+            stock_symbol: "AAPL".to_string(),
+            stock_price,
+            stock_amount,
+            created_epoch,
+            ..Order::default()
+        }
+    }
+
+    #[test]
+    fn test_match_order() {
+        let quote = StockVal {
+            ask_price: 100.5,
+            bid_price: 100.0,
+            volume: 30,
+            ..StockVal::default()
+        };
+
+        /* crossing orders fill at the quote, not at their limit */
+        assert_eq!(
+            match_order(&order(true, 101.0, 10, 0), &quote, 30),
+            Some((10, 100.5))
+        );
+        assert_eq!(
+            match_order(&order(false, 99.0, 10, 0), &quote, 30),
+            Some((10, 100.0))
+        );
+        assert_eq!(match_order(&order(true, 100.0, 10, 0), &quote, 30), None);
+        assert_eq!(match_order(&order(false, 100.5, 10, 0), &quote, 30), None);
+
+        /* the fill is bounded by the volume left and the shares left */
+        assert_eq!(
+            match_order(&order(true, 101.0, 10, 0), &quote, 4),
+            Some((4, 100.5))
+        );
+        let partial = Order {
+            stock_filled: 7,
+            ..order(true, 101.0, 10, 0)
+        };
+        assert_eq!(match_order(&partial, &quote, 30), Some((3, 100.5)));
+        assert_eq!(match_order(&partial, &quote, 0), None);
+
+        let rejected = Order {
+            status: OrderStatus::Rejected,
+            ..order(true, 101.0, 10, 0)
+        };
+        assert_eq!(match_order(&rejected, &quote, 30), None);
+        assert_eq!(
+            match_order(&order(true, 101.0, 10, 0), &StockVal::default(), 30),
+            None
+        );
+
+        /* stops wait for their trigger, then ignore the limit they do not have */
+        let mut stop = Order {
+            order_type: OrderType::Stop,
+            stop_price: 100.0,
+            ..order(false, 0.0, 10, 0)
+        };
+        assert_eq!(match_order(&stop, &quote, 30), None);
+        stop.is_triggered = true;
+        assert_eq!(match_order(&stop, &quote, 30), Some((10, 100.0)));
+        let stop_limit = Order {
+            order_type: OrderType::StopLimit,
+            is_triggered: true,
+            ..order(false, 101.0, 10, 0)
+        };
+        assert_eq!(match_order(&stop_limit, &quote, 30), None);
+    }
+
+    #[test]
+    fn test_order_priority() {
+        let mut buys = [
+            order(true, 100.0, 1, 1),
+            order(true, 101.0, 1, 2),
+            order(true, 100.0, 1, 3),
+        ];
+        buys.sort_by(order_priority);
+        let epochs: Vec<i64> = buys.iter().map(|order| order.created_epoch).collect();
+        assert_eq!(epochs, vec![2, 1, 3]);
+
+        let stop = Order {
+            order_type: OrderType::Stop,
+            ..order(false, 0.0, 1, 3)
+        };
+        let mut sells = [order(false, 100.0, 1, 1), order(false, 99.0, 1, 2), stop];
+        sells.sort_by(order_priority);
+        let epochs: Vec<i64> = sells.iter().map(|order| order.created_epoch).collect();
+        assert_eq!(epochs, vec![3, 2, 1]);
+    }
+}
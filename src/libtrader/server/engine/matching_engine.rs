@@ -0,0 +1,267 @@
+use log::{info, warn};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::account::order::OrderStatus;
+use crate::common::generic::stock_val::StockVal;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_orders::get_open_orders;
+use crate::server::engine::engine_conn::engine_conn;
+use crate::server::engine::fill_order::fill_order;
+use crate::server::engine::match_order::{match_order, order_priority};
+use crate::server::engine::store_trigger::store_trigger;
+use crate::server::engine::trigger_order::trigger_order;
+
+/// Triggers and fills the open orders as new quotes are published.
+///
+/// Quotes are published by ```run_quote_poller()``` as they are stored. The receiver is
+/// subscribed by the caller, so no quote published before the task first runs is missed.
+/// Runs until the feed is closed, on a SQL connection of its own, reconnecting when the
+/// connection is lost. Quotes missed while lagging behind the feed are skipped, the orders
+/// are matched again on the next quote of their stock.
+/// Should be used in Async contexts, with ```IP``` in scope.
+///
+/// Arguments:
+/// quotes - A receiver of the feed of new stock values.
+///
+/// Example:
+/// ```rust
+///     tokio::spawn(IP.scope(local_addr, run_matching_engine(quote_feed.subscribe())));
+/// ```
+pub async fn run_matching_engine(mut quotes: broadcast::Receiver<(String, StockVal)>) {
+    let mut trade_conn: Option<tokio_postgres::Client> = None;
+
+    loop {
+        let (symbol, quote) = match quotes.recv().await {
+            Ok(published) => published,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("MATCHING_ENGINE_LAGGED: {} quotes skipped", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        if let Some(conn) = engine_conn(&mut trade_conn).await {
+            if let Err(err) = match_quote(conn, &symbol, &quote).await {
+                warn!("MATCHING_ENGINE_FAILED: {} {}", symbol, err);
+            }
+        }
+    }
+}
+
+/// Triggers and fills the open orders of a stock against one of its quotes.
+///
+/// The stop orders are evaluated first, see ```trigger_order()```. Buy and sell orders then
+/// each share the volume of the quote, in price then time priority.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own.
+/// symbol - The symbol of the stock.
+/// quote - The new value of the stock.
+///
+/// Returns: nothing on success, ```ReturnFlags``` if the orders could not be read. Orders
+/// failing to fill are logged and skipped.
+async fn match_quote(
+    trade_conn: &mut tokio_postgres::Client,
+    symbol: &str,
+    quote: &StockVal,
+) -> Result<(), ReturnFlags> {
+    let mut orders = Vec::new();
+    for (_, mut order) in get_open_orders(&*trade_conn, symbol).await? {
+        /* immediate orders are only filled when placed */
+        if order.time_in_force.is_immediate() {
+            continue;
+        }
+        /* skip the database transaction of orders the quote does not change */
+        if trigger_order(&mut order.clone(), quote) {
+            order = match store_trigger(trade_conn, order.id, quote).await {
+                Ok(Some(stored)) if stored.status == OrderStatus::Open => stored,
+                /* filled or closed meanwhile */
+                Ok(_) => continue,
+                Err(err) => {
+                    warn!("MATCHING_ENGINE_TRIGGER_FAILED: {} {}", order.id, err);
+                    continue;
+                }
+            };
+            if order.is_triggered {
+                info!("ORDER_TRIGGERED: {}", order.id);
+            }
+        }
+        orders.push(order);
+    }
+    let (mut buys, mut sells): (Vec<_>, Vec<_>) =
+        orders.into_iter().partition(|order| order.is_buy);
+    buys.sort_by(order_priority);
+    sells.sort_by(order_priority);
+
+    let time_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or(0);
+    for orders in [buys, sells] {
+        let mut volume_left = quote.volume;
+        for order in orders {
+            /* skip the database transaction of orders the quote cannot fill */
+            if match_order(&order, quote, volume_left).is_none() {
+                continue;
+            }
+            match fill_order(trade_conn, order.id, quote, volume_left, time_epoch).await {
+                Ok((order, Some(fill))) => {
+                    volume_left -= fill.quantity;
+                    info!(
+                        "ORDER_FILLED: {} {} {} at {}",
+                        order.id, fill.stock_symbol, fill.quantity, fill.price
+                    );
+                }
+                Ok((_, None)) => {}
+                Err(err) => warn!("MATCHING_ENGINE_FILL_FAILED: {} {}", order.id, err),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::account::order::{Order, OrderType};
+    use crate::common::account::position::Position;
+    use crate::server::db::cmd::create_order::create_order;
+    use crate::server::db::cmd::create_position::create_position;
+    use crate::server::db::cmd::create_stock_val::create_stock_val;
+    use crate::server::db::cmd::get_orders::get_order;
+    use crate::server::db::test_db::{test_account, test_db_admin, test_db_trade_conn, test_stock};
+    use crate::server::ds::quote_feed::QuoteFeed;
+    use crate::server::engine::quote_poller::{latest_quote_ids, poll_quotes};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_stored_quote_fills_resting_order() {
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 100_000).await;
+        let quote = StockVal {
+            isin: symbol.clone(),
+            time_epoch: 1_000,
+            ask_price: 105.0,
+            bid_price: 104.0,
+            volume: 100,
+            ..StockVal::default()
+        };
+        create_stock_val(&admin_conn, &symbol, &quote)
+            .await
+            .unwrap();
+
+        /* a limit buy resting below the market */
+        let order = Order {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            stock_price: 100.0,
+            stock_amount: 5,
+            ..Order::default()
+        };
+        let order_id = create_order(&trade_conn, user_id, &order).await.unwrap();
+
+        let quote_feed = QuoteFeed::new();
+        let engine = tokio::spawn(run_matching_engine(quote_feed.subscribe()));
+        let mut last_ids = latest_quote_ids(&trade_conn).await.unwrap();
+
+        /* the market drops to the limit */
+        let stored = StockVal {
+            time_epoch: 1_001,
+            ask_price: 99.5,
+            bid_price: 99.0,
+            ..quote
+        };
+        create_stock_val(&admin_conn, &symbol, &stored)
+            .await
+            .unwrap();
+        poll_quotes(&trade_conn, &quote_feed, &mut last_ids)
+            .await
+            .unwrap();
+
+        let filled = wait_filled(&trade_conn, order_id).await;
+        engine.abort();
+
+        let filled = filled.expect("the order was not filled");
+        assert_eq!(filled.stock_filled, 5);
+        assert!(filled.is_filled);
+    }
+
+    #[tokio::test]
+    async fn test_stored_quote_triggers_stop_order() {
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 0).await;
+        let position = Position {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            stock_open_amount: 5,
+            stock_open_price: 100.0,
+            is_open: true,
+            ..Position::default()
+        };
+        create_position(&trade_conn, user_id, 0, position)
+            .await
+            .unwrap();
+
+        /* a stop loss below the market */
+        let order = Order {
+            stock_symbol: symbol.clone(),
+            order_type: OrderType::Stop,
+            stock_amount: 5,
+            stop_price: 95.0,
+            ..Order::default()
+        };
+        let order_id = create_order(&trade_conn, user_id, &order).await.unwrap();
+
+        let quote_feed = QuoteFeed::new();
+        let engine = tokio::spawn(run_matching_engine(quote_feed.subscribe()));
+        let mut last_ids = latest_quote_ids(&trade_conn).await.unwrap();
+
+        /* the market falls through the stop */
+        let stored = StockVal {
+            isin: symbol.clone(),
+            time_epoch: 1_000,
+            ask_price: 94.5,
+            bid_price: 94.0,
+            volume: 100,
+            ..StockVal::default()
+        };
+        create_stock_val(&admin_conn, &symbol, &stored)
+            .await
+            .unwrap();
+        poll_quotes(&trade_conn, &quote_feed, &mut last_ids)
+            .await
+            .unwrap();
+
+        let filled = wait_filled(&trade_conn, order_id).await;
+        engine.abort();
+
+        let filled = filled.expect("the order was not filled");
+        assert!(filled.is_triggered);
+        assert_eq!(filled.stock_filled, 5);
+    }
+
+    /// Waits for the matching engine to fill an order.
+    async fn wait_filled(trade_conn: &tokio_postgres::Client, order_id: i64) -> Option<Order> {
+        for _ in 0..100 {
+            let (_, order) = get_order(trade_conn, order_id).await.unwrap().unwrap();
+            if order.status == OrderStatus::Filled {
+                return Some(order);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        None
+    }
+}
@@ -0,0 +1,11 @@
+pub mod close_order;
+pub mod engine_conn;
+pub mod expire_orders;
+pub mod fill_order;
+pub mod match_order;
+pub mod matching_engine;
+pub mod pay_dividends;
+pub mod quote_poller;
+pub mod resolve_group;
+pub mod store_trigger;
+pub mod trigger_order;
@@ -0,0 +1,147 @@
+use log::{info, warn};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::account::pay_dividend::pay_dividend;
+use crate::server::db::cmd::get_dividends::{get_dividend_for_update, get_due_dividends};
+use crate::server::db::cmd::update_dividend::set_dividend_paid;
+use crate::server::engine::engine_conn::{begin_transaction, commit_transaction, engine_conn};
+
+/// Time between two searches of due dividends.
+pub static DIVIDEND_PAYMENT_PERIOD: Duration = Duration::from_secs(60);
+
+/// Pays the dividends stored in ```public.dividends``` once due, every
+/// ```DIVIDEND_PAYMENT_PERIOD```.
+///
+/// Runs forever, on a SQL connection of its own.
+/// Should be used in Async contexts, with ```IP``` in scope.
+///
+/// Example:
+/// ```rust
+///     tokio::spawn(IP.scope(local_addr, run_dividend_payments()));
+/// ```
+pub async fn run_dividend_payments() {
+    let mut ticks = tokio::time::interval(DIVIDEND_PAYMENT_PERIOD);
+    let mut trade_conn: Option<tokio_postgres::Client> = None;
+
+    loop {
+        ticks.tick().await;
+        let conn = match engine_conn(&mut trade_conn).await {
+            Some(conn) => conn,
+            None => continue,
+        };
+
+        let time_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs() as i64)
+            .unwrap_or(0);
+        let due = match get_due_dividends(&*conn, time_epoch).await {
+            Ok(due) => due,
+            Err(err) => {
+                warn!("DIVIDEND_PAYMENT_FAILED: {}", err);
+                continue;
+            }
+        };
+        for dividend_id in due {
+            match pay_due_dividend(conn, dividend_id, time_epoch).await {
+                Ok(Some(paid)) => info!("DIVIDEND_PAID: {} to {} lots", dividend_id, paid),
+                Ok(None) => {}
+                Err(err) => warn!("DIVIDEND_PAYMENT_FAILED: {} {}", dividend_id, err),
+            }
+        }
+    }
+}
+
+/// Pays a dividend and marks it paid, in a single database transaction.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own, no other query may run on it meanwhile.
+/// dividend_id - The id of the dividend to pay.
+/// time_epoch - The unix epoch of the payment.
+///
+/// Returns: the number of lots paid on success, ```None``` if no unpaid dividend has the id,
+/// ```ReturnFlags``` on error.
+///
+/// Example:
+/// ```rust
+///     let paid = pay_due_dividend(&mut trade_conn, dividend_id, time_epoch).await?;
+/// ```
+pub async fn pay_due_dividend(
+    trade_conn: &mut tokio_postgres::Client,
+    dividend_id: i64,
+    time_epoch: i64,
+) -> Result<Option<usize>, ReturnFlags> {
+    let db_transaction = begin_transaction(trade_conn).await?;
+    let (symbol, cents_per_share) =
+        match get_dividend_for_update(&db_transaction, dividend_id).await? {
+            Some(dividend) => dividend,
+            None => return Ok(None),
+        };
+    let paid = pay_dividend(&db_transaction, &symbol, cents_per_share, time_epoch).await?;
+    set_dividend_paid(&db_transaction, dividend_id).await?;
+    commit_transaction(db_transaction).await?;
+    Ok(Some(paid))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::account::position::Position;
+    use crate::server::db::cmd::create_position::create_position;
+    use crate::server::db::cmd::get_cash_balance::get_cash_balance;
+    use crate::server::db::test_db::{test_account, test_db_admin, test_db_trade_conn, test_stock};
+
+    #[tokio::test]
+    async fn test_pay_due_dividend_once() {
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let mut trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 0).await;
+        let position = Position {
+            is_buy: true,
+            stock_symbol: symbol.clone(),
+            stock_open_amount: 10,
+            stock_open_price: 100.0,
+            is_open: true,
+            ..Position::default()
+        };
+        create_position(&trade_conn, user_id, 0, position)
+            .await
+            .unwrap();
+        let dividend_id: i64 = admin_conn
+            .query_one(
+                "INSERT INTO public.dividends (stock_symbol, cents_per_share, pay_epoch)
+                    VALUES ($1, 7, 1000) RETURNING id",
+                &[&symbol],
+            )
+            .await
+            .unwrap()
+            .get(0);
+
+        /* not due yet */
+        assert!(!get_due_dividends(&trade_conn, 999)
+            .await
+            .unwrap()
+            .contains(&dividend_id));
+        assert!(get_due_dividends(&trade_conn, 1_000)
+            .await
+            .unwrap()
+            .contains(&dividend_id));
+
+        assert_eq!(
+            pay_due_dividend(&mut trade_conn, dividend_id, 1_000).await,
+            Ok(Some(1))
+        );
+        assert_eq!(
+            pay_due_dividend(&mut trade_conn, dividend_id, 1_000).await,
+            Ok(None)
+        );
+        assert_eq!(get_cash_balance(&trade_conn, user_id).await, Ok(70));
+    }
+}
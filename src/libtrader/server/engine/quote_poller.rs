@@ -0,0 +1,181 @@
+use log::warn;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_company::get_company_symbols;
+use crate::server::db::cmd::get_stock::{get_stock_from_db_after_id, get_stock_from_db_latest};
+use crate::server::ds::quote_feed::QuoteFeed;
+use crate::server::engine::engine_conn::engine_conn;
+
+/// Time between two reads of the stored stock values.
+pub static QUOTE_POLL_PERIOD: Duration = Duration::from_secs(1);
+
+/// Publishes the stock values stored in the database to the ```QuoteFeed```.
+///
+/// Stock values are stored by the market data importer, see ```create_stock_val()```. They
+/// are read every ```QUOTE_POLL_PERIOD```, and pushed to the subscribed connections and the
+/// matching engine. Values stored before the poller started are not published. Runs forever,
+/// on a SQL connection of its own.
+/// Should be used in Async contexts, with ```IP``` in scope.
+///
+/// Arguments:
+/// quote_feed - The feed to publish the values to.
+///
+/// Example:
+/// ```rust
+///     tokio::spawn(IP.scope(local_addr, run_quote_poller(quote_feed.clone())));
+/// ```
+pub async fn run_quote_poller(quote_feed: QuoteFeed) {
+    let mut ticks = tokio::time::interval(QUOTE_POLL_PERIOD);
+    let mut sql_conn: Option<tokio_postgres::Client> = None;
+    let mut last_ids: Option<HashMap<String, i64>> = None;
+
+    loop {
+        ticks.tick().await;
+        let conn = match engine_conn(&mut sql_conn).await {
+            Some(conn) => conn,
+            None => continue,
+        };
+        let last_ids = match &mut last_ids {
+            Some(last_ids) => last_ids,
+            None => match latest_quote_ids(conn).await {
+                Ok(latest) => last_ids.insert(latest),
+                Err(err) => {
+                    warn!("QUOTE_POLLER_FAILED: {}", err);
+                    continue;
+                }
+            },
+        };
+        if let Err(err) = poll_quotes(conn, &quote_feed, last_ids).await {
+            warn!("QUOTE_POLLER_FAILED: {}", err);
+        }
+    }
+}
+
+/// Returns the id of the latest value of every listed stock.
+///
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+///
+/// Returns: the ids by symbol on success, ```ReturnFlags::ServerDbQueryFailed``` on error.
+/// Stocks without values are left out.
+pub async fn latest_quote_ids(
+    sql_conn: &tokio_postgres::Client,
+) -> Result<HashMap<String, i64>, ReturnFlags> {
+    let mut last_ids = HashMap::new();
+    for symbol in get_company_symbols(sql_conn).await? {
+        if let Ok(quote) = get_stock_from_db_latest(sql_conn, &symbol).await {
+            last_ids.insert(symbol, quote.id);
+        }
+    }
+    Ok(last_ids)
+}
+
+/// Publishes the stock values stored since the last poll.
+///
+/// Listed stocks without a stock table yet are skipped.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// quote_feed - The feed to publish the values to.
+/// last_ids - The id of the last value published of every stock, updated here. Stocks
+/// missing are published from their first value.
+///
+/// Returns: the number of values published on success, ```ReturnFlags::ServerDbQueryFailed```
+/// if the listed stocks could not be read.
+///
+/// Example:
+/// ```rust
+///     let mut last_ids = latest_quote_ids(&sql_conn).await?;
+///     let published = poll_quotes(&sql_conn, &quote_feed, &mut last_ids).await?;
+/// ```
+pub async fn poll_quotes(
+    sql_conn: &tokio_postgres::Client,
+    quote_feed: &QuoteFeed,
+    last_ids: &mut HashMap<String, i64>,
+) -> Result<usize, ReturnFlags> {
+    let mut published = 0;
+    for symbol in get_company_symbols(sql_conn).await? {
+        let last_id = last_ids.get(&symbol).copied().unwrap_or(0);
+        let quotes = match get_stock_from_db_after_id(sql_conn, &symbol, last_id).await {
+            Ok(quotes) => quotes,
+            Err(_) => continue,
+        };
+        for quote in quotes {
+            last_ids.insert(symbol.clone(), quote.id);
+            quote_feed.publish(&symbol, quote);
+            published += 1;
+        }
+    }
+    Ok(published)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::generic::stock_val::StockVal;
+    use crate::server::db::cmd::create_stock_val::create_stock_val;
+    use crate::server::db::test_db::{test_db_admin, test_db_trade_conn, test_stock};
+
+    #[tokio::test]
+    async fn test_poll_quotes_publishes_stored_quotes() {
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let sql_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let quote = StockVal {
+            isin: symbol.clone(),
+            time_epoch: 1_000,
+            ask_price: 10.5,
+            bid_price: 10.0,
+            volume: 100,
+            ..StockVal::default()
+        };
+
+        /* values stored before the poller started are not published */
+        create_stock_val(&admin_conn, &symbol, &quote)
+            .await
+            .unwrap();
+        let mut last_ids = latest_quote_ids(&sql_conn).await.unwrap();
+        let quote_feed = QuoteFeed::new();
+        let mut quotes = quote_feed.subscribe();
+
+        let stored = StockVal {
+            time_epoch: 1_001,
+            ask_price: 11.5,
+            ..quote
+        };
+        create_stock_val(&admin_conn, &symbol, &stored)
+            .await
+            .unwrap();
+        poll_quotes(&sql_conn, &quote_feed, &mut last_ids)
+            .await
+            .unwrap();
+
+        /* other tests may store values of their own stocks meanwhile */
+        let mut received = Vec::new();
+        while let Ok((published, quote)) = quotes.try_recv() {
+            if published == symbol {
+                received.push(quote);
+            }
+        }
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].time_epoch, 1_001);
+        assert_eq!(received[0].ask_price, 11.5);
+
+        /* values are published once */
+        poll_quotes(&sql_conn, &quote_feed, &mut last_ids)
+            .await
+            .unwrap();
+        while let Ok((published, _)) = quotes.try_recv() {
+            assert_ne!(published, symbol);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use tokio_postgres::GenericClient;
+
+use crate::common::account::order::{GroupRole, Order, OrderStatus};
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_orders::get_group_orders_for_update;
+use crate::server::db::cmd::update_order::update_order;
+
+/// Applies the rules of its group to the other orders of a group, after an order changed.
+///
+/// See ```group_updates()``` for the rules. Orders belonging to no group are left alone.
+/// Should be used within the database transaction that changed the order.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection or database transaction to use.
+/// order - The order as stored after the change.
+/// filled - Whether the change was a fill.
+///
+/// Returns: nothing on success, ```ReturnFlags``` on error.
+///
+/// Example:
+/// ```rust
+///     update_order(&db_transaction, &order).await?;
+///     resolve_group(&db_transaction, &order, true).await?;
+/// ```
+pub async fn resolve_group<C: GenericClient>(
+    sql_conn: &C,
+    order: &Order,
+    filled: bool,
+) -> Result<(), ReturnFlags> {
+    if order.group_id == 0 {
+        return Ok(());
+    }
+    let others = get_group_orders_for_update(sql_conn, order.group_id)
+        .await?
+        .into_iter()
+        .map(|(_, other)| other)
+        .filter(|other| other.id != order.id)
+        .collect();
+    for other in group_updates(order, filled, others) {
+        update_order(sql_conn, &other).await?;
+    }
+    Ok(())
+}
+
+/// Returns the other orders of a group changed by a change of ```order```.
+///
+/// A leg that fills, even partially, cancels the other legs still waiting. Once the entry of
+/// a bracket can no longer change, its pending exits are activated for the shares it filled,
+/// or cancelled if it filled none.
+///
+/// Arguments:
+/// order - The order that changed.
+/// filled - Whether the change was a fill.
+/// others - The other orders of the group.
+///
+/// Returns: the orders that changed, to be stored.
+fn group_updates(order: &Order, filled: bool, others: Vec<Order>) -> Vec<Order> {
+    let waiting = |other: &Order| {
+        other.group_role == GroupRole::Leg
+            && matches!(other.status, OrderStatus::Open | OrderStatus::Pending)
+    };
+
+    match order.group_role {
+        GroupRole::Leg if filled => others
+            .into_iter()
+            .filter(waiting)
+            .map(|mut other| {
+                other.status = OrderStatus::Cancelled;
+                other
+            })
+            .collect(),
+        GroupRole::Entry if order.status.is_final() => others
+            .into_iter()
+            .filter(|other| other.status == OrderStatus::Pending)
+            .map(|mut other| {
+                if order.stock_filled > 0 {
+                    other.status = OrderStatus::Open;
+                    other.stock_amount = order.stock_filled;
+                } else {
+                    other.status = OrderStatus::Cancelled;
+                }
+                other
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn order(id: i64, group_role: GroupRole, status: OrderStatus, stock_amount: i64) -> Order {
+        Order {
+            id,
+            stock_symbol: "AAPL".to_string(),
+            stock_amount,
+            status,
+            group_id: 1,
+            group_role,
+            ..Order::default()
+        }
+    }
+
+    #[test]
+    fn test_group_updates_oco() {
+        let filled = Order {
+            stock_filled: 3,
+            ..order(1, GroupRole::Leg, OrderStatus::Open, 10)
+        };
+        let others = vec![order(2, GroupRole::Leg, OrderStatus::Open, 10)];
+        let updates = group_updates(&filled, true, others.clone());
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].status, OrderStatus::Cancelled);
+
+        /* a rejected leg leaves the other one alone */
+        let rejected = order(1, GroupRole::Leg, OrderStatus::Rejected, 10);
+        assert!(group_updates(&rejected, false, others).is_empty());
+    }
+
+    #[test]
+    fn test_group_updates_bracket() {
+        let exits = vec![
+            order(2, GroupRole::Leg, OrderStatus::Pending, 10),
+            order(3, GroupRole::Leg, OrderStatus::Pending, 10),
+        ];
+
+        /* a partially filled entry keeps its exits waiting */
+        let entry = Order {
+            stock_filled: 4,
+            ..order(1, GroupRole::Entry, OrderStatus::Open, 10)
+        };
+        assert!(group_updates(&entry, true, exits.clone()).is_empty());
+
+        /* exits follow the shares the entry filled */
+        let entry = Order {
+            status: OrderStatus::Cancelled,
+            ..entry
+        };
+        let updates = group_updates(&entry, false, exits.clone());
+        assert_eq!(updates.len(), 2);
+        assert!(updates
+            .iter()
+            .all(|exit| exit.status == OrderStatus::Open && exit.stock_amount == 4));
+
+        let entry = order(1, GroupRole::Entry, OrderStatus::Rejected, 10);
+        let updates = group_updates(&entry, false, exits.clone());
+        assert!(updates
+            .iter()
+            .all(|exit| exit.status == OrderStatus::Cancelled));
+
+        /* the first exit to fill cancels the other */
+        let exit = Order {
+            stock_filled: 10,
+            status: OrderStatus::Filled,
+            ..exits[0].clone()
+        };
+        let others = vec![
+            Order {
+                stock_filled: 10,
+                status: OrderStatus::Filled,
+                ..order(1, GroupRole::Entry, OrderStatus::Filled, 10)
+            },
+            Order {
+                status: OrderStatus::Open,
+                ..exits[1].clone()
+            },
+        ];
+        let updates = group_updates(&exit, true, others);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(
+            (updates[0].id, updates[0].status),
+            (3, OrderStatus::Cancelled)
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::common::account::order::Order;
+use crate::common::generic::stock_val::StockVal;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_orders::get_order_for_update;
+use crate::server::db::cmd::update_order::update_order_trigger;
+use crate::server::engine::engine_conn::{begin_transaction, commit_transaction};
+use crate::server::engine::trigger_order::trigger_order;
+
+/// Evaluates the trigger of a stored stop order against a quote, and stores the changes.
+///
+/// The order is read again and locked for the evaluation, so the stop of an order amended
+/// since it was last read is used, and an amendment is never overwritten. See
+/// ```trigger_order()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// trade_conn - A SQL connection of its own, no other query may run on it meanwhile.
+/// order_id - The id of the order.
+/// quote - The latest value of the stock.
+///
+/// Returns: the order as stored on success, ```None``` if no order has the id,
+/// ```ReturnFlags``` on error.
+///
+/// Example:
+/// ```rust
+///     if let Some(order) = store_trigger(&mut trade_conn, order_id, &quote).await? {
+///         info!("order {} triggered: {}", order.id, order.is_triggered);
+///     }
+/// ```
+pub async fn store_trigger(
+    trade_conn: &mut tokio_postgres::Client,
+    order_id: i64,
+    quote: &StockVal,
+) -> Result<Option<Order>, ReturnFlags> {
+    let db_transaction = begin_transaction(trade_conn).await?;
+    let mut order = match get_order_for_update(&db_transaction, order_id).await? {
+        Some((_, order)) => order,
+        None => return Ok(None),
+    };
+    if trigger_order(&mut order, quote) {
+        update_order_trigger(&db_transaction, &order).await?;
+    }
+    commit_transaction(db_transaction).await?;
+    Ok(Some(order))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::account::order::OrderType;
+    use crate::server::db::cmd::create_order::create_order;
+    use crate::server::db::cmd::get_orders::get_order;
+    use crate::server::db::cmd::update_order::update_order_terms;
+    use crate::server::db::test_db::{test_account, test_db_admin, test_db_trade_conn, test_stock};
+
+    #[tokio::test]
+    async fn test_store_trigger_reads_amended_stop() {
+        let admin_conn = match test_db_admin().await {
+            Some(conn) => conn,
+            None => return,
+        };
+        let mut trade_conn = test_db_trade_conn().await;
+        let symbol = test_stock(&admin_conn).await;
+        let user_id = test_account(&admin_conn, 0).await;
+        let quote = |bid_price: f64| StockVal {
+            isin: symbol.clone(),
+            ask_price: bid_price + 0.5,
+            bid_price,
+            volume: 100,
+            ..StockVal::default()
+        };
+
+        let mut order = Order {
+            stock_symbol: symbol.clone(),
+            order_type: OrderType::Stop,
+            stock_amount: 10,
+            stop_price: 95.0,
+            ..Order::default()
+        };
+        order.id = create_order(&trade_conn, user_id, &order).await.unwrap();
+
+        /* the stop is amended after the engine read the order */
+        update_order_terms(
+            &trade_conn,
+            &Order {
+                stop_price: 90.0,
+                ..order.clone()
+            },
+        )
+        .await
+        .unwrap();
+        let stored = store_trigger(&mut trade_conn, order.id, &quote(93.0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored.is_triggered);
+        let (_, stored) = get_order(&trade_conn, order.id).await.unwrap().unwrap();
+        assert_eq!(stored.stop_price, 90.0);
+        assert!(!stored.is_triggered);
+
+        let stored = store_trigger(&mut trade_conn, order.id, &quote(89.0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.is_triggered);
+        let (_, stored) = get_order(&trade_conn, order.id).await.unwrap().unwrap();
+        assert!(stored.is_triggered);
+        assert_eq!(stored.stop_price, 90.0);
+
+        assert_eq!(
+            store_trigger(&mut trade_conn, -1, &quote(89.0)).await,
+            Ok(None)
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::common::account::order::{Order, OrderStatus, OrderType};
+use crate::common::generic::stock_val::StockVal;
+
+/// Evaluates the trigger of a stop order against a quote.
+///
+/// Sell stops watch the bid and trigger once it falls to their ```stop_price```, buy stops
+/// watch the ask and trigger once it rises to it. A trailing stop first moves its
+/// ```water_mark``` to the best price seen, keeping its stop ```trail_amount``` behind it.
+/// The first quote seen by a trailing stop sets its mark.
+///
+/// Arguments:
+/// order - The order, updated in place.
+/// quote - The latest value of the stock.
+///
+/// Returns: whether the order changed and must be stored. Limit orders, triggered orders and
+/// orders that are not open never change.
+///
+/// Example:
+/// ```rust
+///     if trigger_order(&mut order, &quote) {
+///         update_order_trigger(&db_transaction, &order).await?;
+///     }
+/// ```
+pub fn trigger_order(order: &mut Order, quote: &StockVal) -> bool {
+    if order.status != OrderStatus::Open || !order.order_type.is_stop() || order.is_triggered {
+        return false;
+    }
+    let price = if order.is_buy {
+        quote.ask_price
+    } else {
+        quote.bid_price
+    };
+    if !price.is_finite() || price <= 0.0 {
+        return false;
+    }
+
+    let mut changed = false;
+    if order.order_type == OrderType::TrailingStop {
+        let improved = order.water_mark <= 0.0
+            || if order.is_buy {
+                price < order.water_mark
+            } else {
+                price > order.water_mark
+            };
+        if improved {
+            order.water_mark = price;
+            order.stop_price = if order.is_buy {
+                price + order.trail_amount
+            } else {
+                price - order.trail_amount
+            };
+            changed = true;
+        }
+    }
+
+    let crossed = if order.is_buy {
+        price >= order.stop_price
+    } else {
+        price <= order.stop_price
+    };
+    if crossed {
+        order.is_triggered = true;
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn quote(bid_price: f64, ask_price: f64) -> StockVal {
+        StockVal {
+            bid_price,
+            ask_price,
+            volume: 100,
+            ..StockVal::default()
+        }
+    }
+
+    #[test]
+    fn test_trigger_stop() {
+        let mut order = Order {
+            order_type: OrderType::Stop,
+            stop_price: 95.0,
+            stock_amount: 10,
+            ..Order::default()
+        };
+        assert!(!trigger_order(&mut order, &quote(96.0, 96.5)));
+        assert!(!order.is_triggered);
+        assert!(trigger_order(&mut order, &quote(95.0, 95.5)));
+        assert!(order.is_triggered);
+        /* triggered orders are left to the matching */
+        assert!(!trigger_order(&mut order, &quote(90.0, 90.5)));
+
+        /* buy stops watch the ask */
+        let mut order = Order {
+            is_buy: true,
+            order_type: OrderType::StopLimit,
+            stop_price: 105.0,
+            stock_price: 106.0,
+            stock_amount: 10,
+            ..Order::default()
+        };
+        assert!(!trigger_order(&mut order, &quote(104.9, 104.95)));
+        assert!(trigger_order(&mut order, &quote(105.0, 105.1)));
+
+        let mut order = Order {
+            stock_price: 95.0,
+            stock_amount: 10,
+            ..Order::default()
+        };
+        assert!(!trigger_order(&mut order, &quote(90.0, 90.5)));
+    }
+
+    #[test]
+    fn test_trigger_trailing_stop() {
+        let mut order = Order {
+            order_type: OrderType::TrailingStop,
+            trail_amount: 5.0,
+            stock_amount: 10,
+            ..Order::default()
+        };
+
+        /* the first quote sets the mark, higher bids raise it */
+        assert!(trigger_order(&mut order, &quote(100.0, 100.5)));
+        assert_eq!((order.water_mark, order.stop_price), (100.0, 95.0));
+        assert!(trigger_order(&mut order, &quote(110.0, 110.5)));
+        assert_eq!((order.water_mark, order.stop_price), (110.0, 105.0));
+
+        /* lower bids leave the stop where it is */
+        assert!(!trigger_order(&mut order, &quote(106.0, 106.5)));
+        assert_eq!(order.stop_price, 105.0);
+        assert!(trigger_order(&mut order, &quote(104.0, 104.5)));
+        assert!(order.is_triggered);
+
+        /* buy trailing stops follow the lowest ask */
+        let mut order = Order {
+            is_buy: true,
+            order_type: OrderType::TrailingStop,
+            trail_amount: 2.0,
+            stock_amount: 10,
+            ..Order::default()
+        };
+        assert!(trigger_order(&mut order, &quote(99.5, 100.0)));
+        assert!(trigger_order(&mut order, &quote(97.5, 98.0)));
+        assert_eq!(order.stop_price, 100.0);
+        assert!(trigger_order(&mut order, &quote(99.9, 100.0)));
+        assert!(order.is_triggered);
+    }
+}
@@ -2,18 +2,37 @@ use std::io;
 use std::net::ToSocketAddrs;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use argh::FromArgs;
-use log::warn;
+use log::{info, warn};
 
-use tokio::io::AsyncReadExt;
 use tokio::net::TcpListener;
+use tokio::time::timeout;
 use tokio_rustls::TlsAcceptor;
+use tokio_util::codec::Framed;
 
+use crate::common::message::compression::Compression;
+use crate::common::message::hello::Hello;
+use crate::common::message::message_codec::MessageCodec;
+use crate::common::network::transport::Transport;
 use crate::server::network::gen_tls_server_config::gen_tls_server_config;
 
 use crate::server::db::initializer::db_connect;
+use crate::server::ds::cost_model::{parse_slippage, CostModel};
+use crate::server::ds::quote_feed::QuoteFeed;
+use crate::server::ds::trading_config::{
+    parse_session_close, set_trading_config, TradingConfig, DEFAULT_STARTING_CASH,
+};
+use crate::server::engine::expire_orders::run_order_expiry;
+use crate::server::engine::matching_engine::run_matching_engine;
+use crate::server::engine::pay_dividends::run_dividend_payments;
+use crate::server::engine::quote_poller::run_quote_poller;
+use crate::server::network::cmd::hello::hello;
+use crate::server::network::handle_connection::handle_connection;
 use crate::server::network::handle_data::handle_data;
+use crate::server::network::websocket_gateway::handle_websocket;
+use crate::server::rest::router::rest_router;
 
 /// Server Options
 #[derive(FromArgs)]
@@ -29,6 +48,46 @@ struct Options {
     /// key file
     #[argh(option, short = 'k')]
     key: PathBuf,
+
+    /// seconds without receiving anything before a client is disconnected
+    #[argh(option, default = "90")]
+    idle_timeout: u64,
+
+    /// unix socket accepting local clients without TLS, disabled if not set
+    #[argh(option)]
+    unix_socket: Option<PathBuf>,
+
+    /// addr accepting JSON WebSocket clients, disabled if not set
+    #[argh(option)]
+    websocket: Option<String>,
+
+    /// addr serving the HTTP REST API, disabled if not set
+    #[argh(option)]
+    rest: Option<String>,
+
+    /// cash balance of new accounts, in whole currency units
+    #[argh(option, default = "DEFAULT_STARTING_CASH / 100")]
+    starting_cash: i64,
+
+    /// close of the exchange sessions, as HH:MM UTC
+    #[argh(option, default = "String::from(\"21:00\")")]
+    session_close: String,
+
+    /// fee charged per trade, in currency units
+    #[argh(option, default = "0.0")]
+    flat_fee: f64,
+
+    /// fee charged per share traded, in currency units
+    #[argh(option, default = "0.0")]
+    share_fee: f64,
+
+    /// commission charged on the value traded, in percent
+    #[argh(option, default = "0.0")]
+    commission: f64,
+
+    /// slippage of the fills, as none, fixed:BPS or volume:BPS
+    #[argh(option, default = "String::from(\"none\")")]
+    slippage: String,
 }
 
 tokio::task_local! {
@@ -162,29 +221,98 @@ pub async fn libtrader_init_server() -> std::io::Result<()> {
     let config = gen_tls_server_config(&options.cert, &options.key)?;
     let acceptor = TlsAcceptor::from(config);
 
+    let idle_timeout = Duration::from_secs(options.idle_timeout);
+
+    // Settings of the simulated market
+    let starting_cash = options
+        .starting_cash
+        .checked_mul(100)
+        .filter(|cents| *cents >= 0)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "INVALID_STARTING_CASH"))?;
+    let session_close = parse_session_close(&options.session_close)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "INVALID_SESSION_CLOSE"))?;
+    let slippage = parse_slippage(&options.slippage)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "INVALID_SLIPPAGE"))?;
+    let cost_model = CostModel {
+        flat_fee: (options.flat_fee * 100.0).round() as i64,
+        share_fee: options.share_fee * 100.0,
+        commission: options.commission / 100.0,
+        slippage,
+    };
+    if !cost_model.is_valid() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "INVALID_FEES"));
+    }
+    let _ = set_trading_config(TradingConfig {
+        starting_cash,
+        session_close,
+        cost_model,
+    });
+
     let listener = TcpListener::bind(&addr).await?;
 
+    // Feed of stock values pushed to subscribed clients, read from the stored values
+    let quote_feed = QuoteFeed::new();
+    let engine_addr: std::net::SocketAddr = ([127, 0, 0, 1], 0).into();
+    tokio::spawn(IP.scope(engine_addr, run_quote_poller(quote_feed.clone())));
+
+    // Orders are filled as the quotes of their stock come, and expire in the background
+    tokio::spawn(IP.scope(engine_addr, run_matching_engine(quote_feed.subscribe())));
+    tokio::spawn(IP.scope(engine_addr, run_order_expiry()));
+
+    // Dividends are paid to the open lots of their stock once due
+    tokio::spawn(IP.scope(engine_addr, run_dividend_payments()));
+
+    // Local clients skip TLS, the socket file permissions protect them
+    #[cfg(unix)]
+    if let Some(path) = options.unix_socket {
+        let unix_listener = bind_unix_socket(&path)?;
+        info!("Listening on unix socket {}", path.display());
+        tokio::spawn(serve_unix_socket(
+            unix_listener,
+            sql_shared_conn.clone(),
+            quote_feed.clone(),
+            idle_timeout,
+        ));
+    }
+    #[cfg(not(unix))]
+    if options.unix_socket.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "UNIX_SOCKET_UNSUPPORTED",
+        ));
+    }
+
+    // Browser clients speak JSON over WebSocket
+    if let Some(websocket_addr) = options.websocket {
+        let websocket_listener = TcpListener::bind(&websocket_addr).await?;
+        info!("Listening for WebSocket clients on {}", websocket_addr);
+        tokio::spawn(serve_websocket(
+            websocket_listener,
+            sql_shared_conn.clone(),
+            idle_timeout,
+        ));
+    }
+
+    // HTTP clients use the REST API, described by GET /openapi.json
+    if let Some(rest_addr) = options.rest {
+        let rest_listener = TcpListener::bind(&rest_addr).await?;
+        info!("Serving the REST API on {}", rest_addr);
+        tokio::spawn(serve_rest(rest_listener, sql_shared_conn.clone()));
+    }
+
     loop {
         let (socket, peer_addr) = listener.accept().await?; // socket, peer_addr
         let acceptor = acceptor.clone();
         let sql_conn = sql_shared_conn.clone();
+        let quote_feed = quote_feed.clone();
 
         // function to run in the thread
         let fut = async move {
-            let mut socket = acceptor.accept(socket).await?;
-            loop {
-                let mut buf = Vec::with_capacity(4096);
-                socket.read_buf(&mut buf).await?;
-                match handle_data(&sql_conn, &mut socket, buf.as_slice()).await {
-                    Ok(()) => {}
-                    Err(err) => {
-                        warn!("{}", format!("Failed running handle_data: {:#?}", err));
-                        break;
-                    }
-                };
-            }
-
-            Ok(()) as std::io::Result<()>
+            // peers stalling the handshake are dropped after the idle timeout
+            let socket = timeout(idle_timeout, acceptor.accept(socket))
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "TLS_ACCEPT_TIMED_OUT"))??;
+            serve_connection(socket, &sql_conn, &quote_feed, idle_timeout).await
         };
 
         tokio::spawn(async move {
@@ -197,3 +325,226 @@ pub async fn libtrader_init_server() -> std::io::Result<()> {
         });
     }
 }
+
+/// Serves a connected client, once its transport is set up.
+///
+/// Runs the hello exchange, which picks the compression of the connection, then handles the
+/// requests of the client until it disconnects.
+/// Should be used in contexts that return ```io::Result```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// socket - The connection to the client.
+/// sql_conn - The SQL connection to use.
+/// quote_feed - The feed of new stock values.
+/// idle_timeout - The longest time allowed between two messages of the client.
+///
+/// Returns: ```io::Result``` failing if the hello exchange failed.
+async fn serve_connection<T: Transport>(
+    socket: T,
+    sql_conn: &tokio_postgres::Client,
+    quote_feed: &QuoteFeed,
+    idle_timeout: Duration,
+) -> io::Result<()> {
+    let mut socket = Framed::new(socket, MessageCodec::new());
+
+    // refuse clients speaking an incompatible protocol
+    let client_hello = timeout(idle_timeout, hello(&mut socket))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "HELLO_TIMED_OUT"))??;
+    info!("Client connected: {}", client_hello);
+    let compression = Compression::negotiate(Hello::new().shared_capabilities(&client_hello));
+
+    if let Err(err) =
+        handle_connection(sql_conn, quote_feed, idle_timeout, compression, &mut socket).await
+    {
+        warn!("Failed running handle_connection: {:#?}", err);
+    }
+
+    Ok(())
+}
+
+/// Binds the unix socket listener.
+///
+/// A socket file left over by a previous run is replaced, any other file at ```path``` is an
+/// error. The socket is only accessible to the user running the server. It is bound inside a
+/// directory only the user can enter, and moved to ```path``` once its permissions are set, so
+/// no other user can connect in between.
+///
+/// Arguments:
+/// path - The path of the socket file.
+///
+/// Returns: ```io::Result``` wrapping the listener.
+#[cfg(unix)]
+fn bind_unix_socket(path: &std::path::Path) -> io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("UNIX_SOCKET_PATH_IN_USE: {}", path.display()),
+            ))
+        }
+        Err(_) => {}
+    }
+
+    let mut private_dir = path.as_os_str().to_owned();
+    private_dir.push(format!(".{}.d", std::process::id()));
+    let private_dir = std::path::PathBuf::from(private_dir);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+
+    let private_path = private_dir.join("socket");
+    let bound = tokio::net::UnixListener::bind(&private_path).and_then(|listener| {
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&private_path, path)?;
+        Ok(listener)
+    });
+    /* the socket file is left behind if the rename failed */
+    let _ = std::fs::remove_file(&private_path);
+    let _ = std::fs::remove_dir(&private_dir);
+    bound
+}
+
+/// Accepts clients on the unix socket listener, without TLS.
+///
+/// Accept errors are logged and the listener keeps running.
+///
+/// Arguments:
+/// listener - The bound unix socket listener.
+/// sql_conn - The SQL connection to use.
+/// quote_feed - The feed of new stock values.
+/// idle_timeout - The longest time allowed between two messages of a client.
+#[cfg(unix)]
+async fn serve_unix_socket(
+    listener: tokio::net::UnixListener,
+    sql_conn: Arc<tokio_postgres::Client>,
+    quote_feed: QuoteFeed,
+    idle_timeout: Duration,
+) {
+    // unix peers have no address, log them as local
+    let local_addr: std::net::SocketAddr = ([127, 0, 0, 1], 0).into();
+
+    IP.scope(local_addr, async move {
+        loop {
+            let socket = match listener.accept().await {
+                Ok((socket, _)) => socket,
+                Err(err) => {
+                    warn!("UNIX_SOCKET_ACCEPT_FAILED: {}", err);
+                    continue;
+                }
+            };
+            let sql_conn = sql_conn.clone();
+            let quote_feed = quote_feed.clone();
+
+            tokio::spawn(IP.scope(local_addr, async move {
+                if let Err(err) =
+                    serve_connection(socket, &sql_conn, &quote_feed, idle_timeout).await
+                {
+                    eprintln!("{:?}", err);
+                }
+            }));
+        }
+    })
+    .await;
+}
+
+/// Accepts JSON WebSocket clients, see ```handle_websocket()```.
+///
+/// Accept errors are logged and the listener keeps running.
+///
+/// Arguments:
+/// listener - The bound TCP listener.
+/// sql_conn - The SQL connection to use.
+/// idle_timeout - The longest time allowed between two messages of a client.
+async fn serve_websocket(
+    listener: TcpListener,
+    sql_conn: Arc<tokio_postgres::Client>,
+    idle_timeout: Duration,
+) {
+    // the listener itself logs without a peer
+    let local_addr: std::net::SocketAddr = ([127, 0, 0, 1], 0).into();
+
+    IP.scope(local_addr, async move {
+        loop {
+            let (socket, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    warn!("WEBSOCKET_ACCEPT_FAILED: {}", err);
+                    continue;
+                }
+            };
+            let sql_conn = sql_conn.clone();
+
+            tokio::spawn(IP.scope(peer_addr, async move {
+                if let Err(err) = handle_websocket(socket, idle_timeout, |request| {
+                    handle_data(&sql_conn, request)
+                })
+                .await
+                {
+                    eprintln!("{:?}", err);
+                }
+            }));
+        }
+    })
+    .await;
+}
+
+/// Serves the REST API, see ```rest_router()```.
+///
+/// Every HTTP request is handled with the ```IP``` of its peer in scope.
+///
+/// Arguments:
+/// listener - The bound TCP listener.
+/// sql_conn - The SQL connection to use.
+async fn serve_rest(listener: TcpListener, sql_conn: Arc<tokio_postgres::Client>) {
+    use axum::extract::ConnectInfo;
+    use axum::middleware::{from_fn, Next};
+
+    // connections are served in tasks spawned by axum, which do not inherit the scope
+    let router = rest_router(sql_conn).layer(from_fn(
+        |ConnectInfo(peer_addr): ConnectInfo<std::net::SocketAddr>,
+         request: axum::extract::Request,
+         next: Next| IP.scope(peer_addr, next.run(request)),
+    ));
+
+    // the listener itself logs without a peer
+    let local_addr: std::net::SocketAddr = ([127, 0, 0, 1], 0).into();
+
+    IP.scope(local_addr, async move {
+        let service = router.into_make_service_with_connect_info::<std::net::SocketAddr>();
+        if let Err(err) = axum::serve(listener, service).await {
+            warn!("REST_SERVE_FAILED: {}", err);
+        }
+    })
+    .await;
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_unix_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("papertrader-{}.sock", std::process::id()));
+        let listener = bind_unix_socket(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        tokio::net::UnixStream::connect(&path).await.unwrap();
+        listener.accept().await.unwrap();
+
+        /* a stale socket is replaced, other files are kept */
+        drop(listener);
+        let listener = bind_unix_socket(&path).unwrap();
+        drop(listener);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(&path, b"data").unwrap();
+        assert!(bind_unix_socket(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 pub mod account;
 pub mod db;
 pub mod ds;
+pub mod engine;
 pub mod initializer;
 pub mod network;
+pub mod rest;
@@ -0,0 +1,20 @@
+use log::warn;
+
+use crate::common::message::request::AmendOrderRequest;
+use crate::common::message::response::Response;
+
+use crate::server::account::amend_order::acc_amend_order;
+
+pub async fn amend_order(
+    sql_conn: &tokio_postgres::Client,
+    amend_req: &AmendOrderRequest,
+) -> Response {
+    /* call acc_amend_order() server version */
+    match acc_amend_order(sql_conn, amend_req).await {
+        Ok(order) => Response::OrderAmended(order),
+        Err(err) => {
+            warn!("AMEND_ORDER_FAILED: {}", err);
+            Response::Error(err, "could not amend the order".to_string())
+        }
+    }
+}
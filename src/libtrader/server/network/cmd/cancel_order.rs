@@ -0,0 +1,20 @@
+use log::warn;
+
+use crate::common::message::request::CancelOrderRequest;
+use crate::common::message::response::Response;
+
+use crate::server::account::cancel_order::acc_cancel_order;
+
+pub async fn cancel_order(
+    sql_conn: &tokio_postgres::Client,
+    cancel_req: &CancelOrderRequest,
+) -> Response {
+    /* call acc_cancel_order() server version */
+    match acc_cancel_order(sql_conn, cancel_req).await {
+        Ok(order) => Response::OrderCancelled(order),
+        Err(err) => {
+            warn!("CANCEL_ORDER_FAILED: {}", err);
+            Response::Error(err, "could not cancel the order".to_string())
+        }
+    }
+}
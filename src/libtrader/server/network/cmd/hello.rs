@@ -0,0 +1,124 @@
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use std::io;
+
+use crate::common::message::hello::Hello;
+use crate::common::message::message_codec::MessageCodec;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+use crate::common::network::transport::Transport;
+
+use crate::server::network::validate_request::validate_request;
+
+use tokio_util::codec::Framed;
+
+/// Runs the server side of the hello exchange.
+///
+/// Waits for the client hello, which must be the first message of the connection. Answers with
+/// the server hello if the protocol versions are compatible. Otherwise the client is sent the
+/// error and the connection is closed.
+/// Should be used in contexts that return ```io::Result```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// socket - The framed connection, over any transport.
+///
+/// Returns: ```io::Result``` wrapping the client ```Hello```.
+///
+/// Example:
+/// ```rust
+///     let client_hello = hello(&mut socket).await?;
+/// ```
+pub async fn hello<T: Transport>(socket: &mut Framed<T, MessageCodec>) -> io::Result<Hello> {
+    let client_msg = match socket.next().await {
+        Some(msg) => msg?,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{}", ReturnFlags::ServerHelloNotReceived),
+            ))
+        }
+    };
+
+    let server_hello = Hello::new();
+    let (flag, detail) = match validate_request(&client_msg) {
+        Ok(Request::Hello(client_hello)) => {
+            if server_hello.is_compatible(&client_hello) {
+                socket
+                    .send(Response::Hello(server_hello).to_message())
+                    .await?;
+                return Ok(client_hello);
+            }
+            warn!(
+                "HELLO_INCOMPATIBLE_CLIENT: {}, server {}",
+                client_hello, server_hello
+            );
+            (
+                ReturnFlags::CommonProtocolVersionMismatch,
+                format!("server speaks {}", server_hello),
+            )
+        }
+        _ => {
+            warn!("HELLO_NOT_RECEIVED");
+            (
+                ReturnFlags::ServerHelloNotReceived,
+                "expected a hello".to_string(),
+            )
+        }
+    };
+
+    /* refuse the connection */
+    socket
+        .send(Response::Error(flag, detail).to_message())
+        .await?;
+    socket.close().await?;
+    Err(io::Error::new(
+        io::ErrorKind::ConnectionRefused,
+        format!("{}", flag),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::hello::PROTOCOL_VERSION;
+
+    #[tokio::test]
+    async fn test_hello_over_duplex() {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut client = Framed::new(client, MessageCodec::new());
+        let mut server = Framed::new(server, MessageCodec::new());
+
+        client
+            .send(Request::Hello(Hello::new()).to_message())
+            .await
+            .unwrap();
+        assert_eq!(hello(&mut server).await.unwrap(), Hello::new());
+        assert_eq!(
+            Response::from_message(&client.next().await.unwrap().unwrap()),
+            Ok(Response::Hello(Hello::new()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hello_refused() {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut client = Framed::new(client, MessageCodec::new());
+        let mut server = Framed::new(server, MessageCodec::new());
+
+        let mut client_hello = Hello::new();
+        client_hello.protocol_version = PROTOCOL_VERSION + 1;
+        client
+            .send(Request::Hello(client_hello).to_message())
+            .await
+            .unwrap();
+        assert!(hello(&mut server).await.is_err());
+        match Response::from_message(&client.next().await.unwrap().unwrap()) {
+            Ok(Response::Error(flag, _)) => {
+                assert_eq!(flag, ReturnFlags::CommonProtocolVersionMismatch)
+            }
+            response => panic!("unexpected response {:?}", response),
+        }
+    }
+}
@@ -1,7 +1,15 @@
+pub mod amend_order;
+pub mod cancel_order;
 pub mod get_asset_data;
 pub mod get_asset_info;
+pub mod hello;
 pub mod login_normal;
+pub mod place_order;
+pub mod place_order_group;
 pub mod purchase_asset;
 pub mod register;
+pub mod retrieve_orders;
 pub mod retrieve_portfolio;
 pub mod retrieve_transactions;
+pub mod sell_asset;
+pub mod subscribe;
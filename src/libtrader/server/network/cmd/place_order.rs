@@ -0,0 +1,20 @@
+use log::warn;
+
+use crate::common::message::request::PlaceOrderRequest;
+use crate::common::message::response::Response;
+
+use crate::server::account::place_order::acc_place_order;
+
+pub async fn place_order(
+    sql_conn: &tokio_postgres::Client,
+    order_req: &PlaceOrderRequest,
+) -> Response {
+    /* call acc_place_order() server version */
+    match acc_place_order(sql_conn, order_req).await {
+        Ok(order) => Response::OrderPlaced(order),
+        Err(err) => {
+            warn!("PLACE_ORDER_FAILED: {}", err);
+            Response::Error(err, "could not place the order".to_string())
+        }
+    }
+}
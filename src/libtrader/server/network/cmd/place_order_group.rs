@@ -0,0 +1,20 @@
+use log::warn;
+
+use crate::common::message::request::PlaceOrderGroupRequest;
+use crate::common::message::response::Response;
+
+use crate::server::account::place_order_group::acc_place_order_group;
+
+pub async fn place_order_group(
+    sql_conn: &tokio_postgres::Client,
+    group_req: &PlaceOrderGroupRequest,
+) -> Response {
+    /* call acc_place_order_group() server version */
+    match acc_place_order_group(sql_conn, group_req).await {
+        Ok(group) => Response::OrderGroupPlaced(group),
+        Err(err) => {
+            warn!("PLACE_ORDER_GROUP_FAILED: {}", err);
+            Response::Error(err, "could not place the order group".to_string())
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use log::warn;
+
+use crate::common::message::response::Response;
+
+use crate::server::account::retrieval_orders::acc_retrieve_orders;
+
+pub async fn retrieve_orders(
+    sql_conn: &tokio_postgres::Client,
+    auth_jwt: String,
+    open: bool,
+) -> Response {
+    /* call acc_retrieve_orders() server version */
+    match acc_retrieve_orders(sql_conn, auth_jwt, open).await {
+        Ok(orders) if open => Response::OpenOrders(orders),
+        Ok(orders) => Response::OrderHistory(orders),
+        Err(err) => {
+            warn!("RETRIEVE_ORDERS_FAILED: {}", err);
+            Response::Error(err, "could not retrieve the orders".to_string())
+        }
+    }
+}
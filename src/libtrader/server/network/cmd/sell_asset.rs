@@ -0,0 +1,20 @@
+use log::warn;
+
+use crate::common::message::request::SellAssetRequest;
+use crate::common::message::response::Response;
+
+use crate::server::account::sell_asset::acc_sell_asset;
+
+pub async fn sell_asset(
+    sql_conn: &tokio_postgres::Client,
+    sell_req: &SellAssetRequest,
+) -> Response {
+    /* call acc_sell_asset() server version */
+    match acc_sell_asset(sql_conn, sell_req).await {
+        Ok(fill) => Response::AssetSold(fill),
+        Err(err) => {
+            warn!("SELL_ASSET_FAILED: {}", err);
+            Response::Error(err, "could not sell the asset".to_string())
+        }
+    }
+}
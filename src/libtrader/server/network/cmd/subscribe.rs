@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use log::warn;
+
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// The most symbols a single connection may be subscribed to.
+pub static MAX_SUBSCRIPTIONS: usize = 256;
+
+/// Returns the subscriptions of a connection, sorted.
+fn sorted(subscriptions: &HashSet<String>) -> Vec<String> {
+    let mut symbols: Vec<String> = subscriptions.iter().cloned().collect();
+    symbols.sort();
+    symbols
+}
+
+/// Subscribes a connection to the quotes of a list of symbols.
+///
+/// Symbols must be non-empty and alphanumeric. Either all symbols are added, or none are.
+///
+/// Arguments:
+/// subscriptions - The subscriptions of the connection.
+/// symbols - The symbols to subscribe to.
+///
+/// Returns: ```Response::Subscribed``` with every symbol subscribed to, ```Response::Error``` on
+/// error.
+///
+/// Example:
+/// ```rust
+///     let response = subscribe(&mut subscriptions, vec!["AAPL".to_string()]);
+/// ```
+pub fn subscribe(subscriptions: &mut HashSet<String>, symbols: Vec<String>) -> Response {
+    if let Some(symbol) = symbols
+        .iter()
+        .find(|symbol| symbol.is_empty() || !symbol.chars().all(char::is_alphanumeric))
+    {
+        warn!("SUBSCRIBE_INVALID_SYMBOL: {}", symbol);
+        return Response::Error(
+            ReturnFlags::ServerSubscribeInvSymbol,
+            format!("invalid symbol {:?}", symbol),
+        );
+    }
+
+    let added = symbols
+        .iter()
+        .filter(|symbol| !subscriptions.contains(*symbol))
+        .collect::<HashSet<_>>()
+        .len();
+    if subscriptions.len() + added > MAX_SUBSCRIPTIONS {
+        return Response::Error(
+            ReturnFlags::ServerSubscribeLimitReached,
+            format!("at most {} symbols can be subscribed to", MAX_SUBSCRIPTIONS),
+        );
+    }
+
+    subscriptions.extend(symbols);
+    Response::Subscribed(sorted(subscriptions))
+}
+
+/// Unsubscribes a connection from the quotes of a list of symbols.
+///
+/// Symbols not subscribed to are ignored.
+///
+/// Arguments:
+/// subscriptions - The subscriptions of the connection.
+/// symbols - The symbols to unsubscribe from.
+///
+/// Returns: ```Response::Unsubscribed``` with every symbol still subscribed to.
+///
+/// Example:
+/// ```rust
+///     let response = unsubscribe(&mut subscriptions, vec!["AAPL".to_string()]);
+/// ```
+pub fn unsubscribe(subscriptions: &mut HashSet<String>, symbols: Vec<String>) -> Response {
+    for symbol in symbols {
+        subscriptions.remove(&symbol);
+    }
+    Response::Unsubscribed(sorted(subscriptions))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_subscribe_unsubscribe() {
+        let mut subscriptions = HashSet::new();
+        assert_eq!(
+            subscribe(
+                &mut subscriptions,
+                vec!["MSFT".to_string(), "AAPL".to_string()]
+            ),
+            Response::Subscribed(vec!["AAPL".to_string(), "MSFT".to_string()])
+        );
+        assert_eq!(
+            unsubscribe(
+                &mut subscriptions,
+                vec!["MSFT".to_string(), "GOOG".to_string()]
+            ),
+            Response::Unsubscribed(vec!["AAPL".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_subscribe_rejected() {
+        let mut subscriptions = HashSet::new();
+        match subscribe(
+            &mut subscriptions,
+            vec!["AAPL".to_string(), "x; DROP TABLE".to_string()],
+        ) {
+            Response::Error(flag, _) => assert_eq!(flag, ReturnFlags::ServerSubscribeInvSymbol),
+            response => panic!("unexpected response {}", response),
+        }
+        assert!(subscriptions.is_empty());
+
+        let symbols: Vec<String> = (0..=MAX_SUBSCRIPTIONS).map(|x| format!("S{}", x)).collect();
+        match subscribe(&mut subscriptions, symbols) {
+            Response::Error(flag, _) => {
+                assert_eq!(flag, ReturnFlags::ServerSubscribeLimitReached)
+            }
+            response => panic!("unexpected response {}", response),
+        }
+        assert!(subscriptions.is_empty());
+    }
+}
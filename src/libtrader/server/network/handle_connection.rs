@@ -0,0 +1,224 @@
+use futures::stream::FuturesUnordered;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use std::collections::HashSet;
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+use crate::common::message::compression::{compress_message, Compression};
+use crate::common::message::message_codec::MessageCodec;
+use crate::common::message::message_parts::{split_message, MAX_PART_DATA_LENGTH};
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+use crate::common::network::transport::Transport;
+
+use crate::server::ds::quote_feed::QuoteFeed;
+use crate::server::network::cmd::subscribe::{subscribe, unsubscribe};
+use crate::server::network::handle_data::handle_data;
+use crate::server::network::validate_request::validate_request;
+
+use tokio::sync::broadcast;
+use tokio::time::{sleep_until, Instant};
+use tokio_util::codec::Framed;
+
+/// Number of requests of a connection handled at once. Further requests are not read until
+/// one of them is answered, so a client pipelining requests is slowed down to the server pace.
+pub static MAX_IN_FLIGHT_REQUESTS: usize = 32;
+
+/// Serves the requests of a connected client until it disconnects.
+///
+/// Every request is checked by ```validate_request()``` before being handled, invalid ones are
+/// answered with an error right away.
+/// Requests are handled concurrently, up to ```MAX_IN_FLIGHT_REQUESTS``` at once. Every response
+/// is sent as soon as it is ready and carries the ```request_id``` of its request, so responses
+/// may be sent out of order. Large responses
+/// are compressed with the compression negotiated for the connection and sent as multiple
+/// parts. Once the client closes its side, the requests still in flight are answered before
+/// returning.
+/// The connection keeps its own set of subscribed symbols, the quotes published on the feed for
+/// those symbols are pushed as ```Response::Quote``` with a ```request_id``` of ```0```.
+/// Clients sending nothing for ```idle_timeout```, not even a ```Request::Ping```, are considered
+/// dead and disconnected.
+/// Should be used in contexts that return ```io::Result```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// quote_feed - The feed of new stock values.
+/// idle_timeout - The longest time allowed between two messages of the client.
+/// compression - The compression negotiated during the hello exchange.
+/// socket - The framed connection, after the hello exchange.
+///
+/// Returns: ```io::Result``` failing if the connection is unusable or idle.
+///
+/// Example:
+/// ```rust
+///     handle_connection(&sql_conn, &quote_feed, idle_timeout, compression, &mut socket).await?;
+/// ```
+pub async fn handle_connection<T: Transport>(
+    sql_conn: &tokio_postgres::Client,
+    quote_feed: &QuoteFeed,
+    idle_timeout: Duration,
+    compression: Compression,
+    socket: &mut Framed<T, MessageCodec>,
+) -> io::Result<()> {
+    serve_requests(quote_feed, idle_timeout, compression, socket, |request| {
+        handle_data(sql_conn, request)
+    })
+    .await
+}
+
+/// Body of ```handle_connection()```, handling the requests with ```handler```.
+async fn serve_requests<T, F, Fut>(
+    quote_feed: &QuoteFeed,
+    idle_timeout: Duration,
+    compression: Compression,
+    socket: &mut Framed<T, MessageCodec>,
+    handler: F,
+) -> io::Result<()>
+where
+    T: Transport,
+    F: Fn(Request) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let mut in_flight = FuturesUnordered::new();
+    let mut last_received = Instant::now();
+    let mut subscriptions: HashSet<String> = HashSet::new();
+    let mut quotes = quote_feed.subscribe();
+
+    loop {
+        tokio::select! {
+            client_msg = socket.next(), if in_flight.len() < MAX_IN_FLIGHT_REQUESTS => match client_msg {
+                Some(client_msg) => {
+                    let client_msg = client_msg?;
+                    last_received = Instant::now();
+                    let request_id = client_msg.request_id;
+                    match validate_request(&client_msg) {
+                        /* subscriptions change the connection state, handle them in order */
+                        Ok(Request::Subscribe(symbols)) => {
+                            let response = subscribe(&mut subscriptions, symbols);
+                            send_response(socket, compression, request_id, response).await?;
+                        }
+                        Ok(Request::Unsubscribe(symbols)) => {
+                            let response = unsubscribe(&mut subscriptions, symbols);
+                            send_response(socket, compression, request_id, response).await?;
+                        }
+                        Ok(request) => {
+                            let response = handler(request);
+                            in_flight.push(async move { (request_id, response.await) });
+                        }
+                        Err(response) => send_response(socket, compression, request_id, response).await?,
+                    }
+                }
+                None => break,
+            },
+            Some((request_id, response)) = in_flight.next() => {
+                /* the client is not read at the cap, its silence meanwhile does not count */
+                if in_flight.len() + 1 >= MAX_IN_FLIGHT_REQUESTS {
+                    last_received = Instant::now();
+                }
+                send_response(socket, compression, request_id, response).await?;
+            }
+            quote = quotes.recv() => match quote {
+                Ok((symbol, stock_val)) => {
+                    if subscriptions.contains(&symbol) {
+                        send_response(socket, compression, 0, Response::Quote(symbol, stock_val)).await?;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("HANDLE_CONNECTION_QUOTES_SKIPPED: {}", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = sleep_until(last_received + idle_timeout), if in_flight.len() < MAX_IN_FLIGHT_REQUESTS => {
+                warn!("HANDLE_CONNECTION_IDLE_TIMEOUT");
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{}", ReturnFlags::ServerConnIdleTimeout),
+                ));
+            }
+        }
+    }
+
+    /* answer the requests left in flight */
+    while let Some((request_id, response)) = in_flight.next().await {
+        send_response(socket, compression, request_id, response).await?;
+    }
+    Ok(())
+}
+
+/// Sends a response, compressed then split in parts of at most ```MAX_PART_DATA_LENGTH``` bytes.
+async fn send_response<T: Transport>(
+    socket: &mut Framed<T, MessageCodec>,
+    compression: Compression,
+    request_id: u64,
+    response: Response,
+) -> io::Result<()> {
+    let mut message = compress_message(response.to_message(), compression);
+    message.request_id = request_id;
+    for part in split_message(message, MAX_PART_DATA_LENGTH) {
+        socket.feed(part).await?;
+    }
+    socket.flush().await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    #[tokio::test]
+    async fn test_serve_requests_in_flight_cap() {
+        let (client, server) = tokio::io::duplex(1024 * 1024);
+        let mut client = Framed::new(client, MessageCodec::new());
+        let mut server = Framed::new(server, MessageCodec::new());
+        let quote_feed = QuoteFeed::new();
+
+        /* requests are held until permits are added */
+        let started = Arc::new(AtomicUsize::new(0));
+        let permits = Arc::new(Semaphore::new(0));
+        let handler = |_| {
+            let started = started.clone();
+            let permits = permits.clone();
+            async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                permits.acquire().await.unwrap().forget();
+                Response::Pong
+            }
+        };
+        let serve = serve_requests(
+            &quote_feed,
+            Duration::from_secs(10),
+            Compression::None,
+            &mut server,
+            handler,
+        );
+
+        let sent = MAX_IN_FLIGHT_REQUESTS + 8;
+        let exchange = async {
+            for request_id in 1..=sent as u64 {
+                let mut message = Request::Ping.to_message();
+                message.request_id = request_id;
+                client.send(message).await.unwrap();
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            assert_eq!(started.load(Ordering::SeqCst), MAX_IN_FLIGHT_REQUESTS);
+
+            /* the requests left are read as the first ones are answered */
+            permits.add_permits(sent);
+            for _ in 0..sent {
+                let response = client.next().await.unwrap().unwrap();
+                assert_eq!(Response::from_message(&response), Ok(Response::Pong));
+            }
+            assert_eq!(started.load(Ordering::SeqCst), sent);
+            client.close().await.unwrap();
+        };
+
+        let (served, _) = tokio::join!(serve, exchange);
+        served.unwrap();
+    }
+}
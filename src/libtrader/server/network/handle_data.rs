@@ -1,132 +1,105 @@
 use data_encoding::HEXUPPER;
 
-use crate::common::message::inst::{CommandInst, DataTransferInst};
-use crate::common::message::message::Message;
-use crate::common::message::message_builder::message_builder;
-use crate::common::message::message_type::MessageType;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
 
+use crate::server::network::cmd::amend_order::amend_order;
+use crate::server::network::cmd::cancel_order::cancel_order;
+use crate::server::network::cmd::get_asset_data::get_asset_data;
 use crate::server::network::cmd::login_normal::login_normal;
+use crate::server::network::cmd::place_order::place_order;
+use crate::server::network::cmd::place_order_group::place_order_group;
+use crate::server::network::cmd::purchase_asset::purchase_asset;
 use crate::server::network::cmd::register::register;
+use crate::server::network::cmd::retrieve_orders::retrieve_orders;
 use crate::server::network::cmd::retrieve_portfolio::retrieve_portfolio;
 use crate::server::network::cmd::retrieve_transactions::retrieve_transactions;
+use crate::server::network::cmd::sell_asset::sell_asset;
 
-//use tokio::net::TcpStream;
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpStream;
-use tokio_rustls::server::TlsStream;
-
-pub async fn handle_data(
-    sql_conn: &tokio_postgres::Client,
-    socket: &mut TlsStream<TcpStream>,
-    buf: &[u8],
-) -> std::io::Result<()> {
-    /* decode incoming message */
-    let client_msg: Message = bincode::deserialize(&buf).map_err(|err| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("HANDLE_DATA_RCVD_INVALID_MSG: {}", err),
-        )
-    })?;
-    //println!("This is a message: {}", client_msg);
-
+/// Handles a single client request.
+///
+/// The request must already be checked by ```validate_request()```. The returned response is
+/// not yet sent, the caller is responsible for matching it with the request.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// request - The validated request.
+///
+/// Returns: the ```Response``` to send, ```Response::Error``` if the request failed.
+///
+/// Example:
+/// ```rust
+///     let response = handle_data(&sql_conn, request).await;
+/// ```
+pub async fn handle_data(sql_conn: &tokio_postgres::Client, request: Request) -> Response {
     /* handle individual client instructions */
-    match client_msg.instruction {
-        _ if client_msg.instruction == CommandInst::GenHashSalt as i64 => {
+    match request {
+        Request::Hello(_) => {
+            /* the hello exchange is only valid at connection start */
+            Response::Error(
+                ReturnFlags::ServerHandleDataRcvdInvMsg,
+                "hello already exchanged".to_string(),
+            )
+        }
+        Request::GenHashSalt => {
             use ring::rand::SecureRandom;
             use ring::{digest, rand};
             let rng = rand::SystemRandom::new();
             let mut salt = [0u8; digest::SHA512_OUTPUT_LEN / 2];
             rng.fill(&mut salt).unwrap();
 
-            let server_response: Message = message_builder(
-                MessageType::DataTransfer,
-                CommandInst::GenHashSalt as i64,
-                1,
-                0,
-                1,
-                salt.to_vec(),
-            );
-            socket
-                .write_all(bincode::serialize(&server_response).unwrap().as_slice())
-                .await
+            Response::HashSalt(salt.to_vec())
         }
-        _ if client_msg.instruction == CommandInst::GetEmailSalt as i64 => {
+        Request::GetEmailSalt(username) => {
             use crate::server::db::cmd::get_user_salt::get_user_salt;
-            match get_user_salt(
-                sql_conn,
-                String::from_utf8(client_msg.data).unwrap().as_str(),
-                true,
-                false,
-            )
-            .await
-            {
-                Ok(salt) => {
-                    let server_response: Message = message_builder(
-                        MessageType::DataTransfer,
-                        CommandInst::GetEmailSalt as i64,
-                        1,
-                        0,
-                        1,
-                        HEXUPPER.decode(salt.as_bytes()).unwrap(),
-                    );
-                    socket
-                        .write_all(bincode::serialize(&server_response).unwrap().as_slice())
-                        .await
-                }
-                Err(_) => {
-                    let server_response =
-                        message_builder(MessageType::ServerReturn, 0, 0, 0, 0, Vec::new());
-                    socket
-                        .write_all(bincode::serialize(&server_response).unwrap().as_slice())
-                        .await
-                }
+            match get_user_salt(sql_conn, username.as_str(), true, false).await {
+                Ok(salt) => match HEXUPPER.decode(salt.as_bytes()) {
+                    Ok(salt) => Response::EmailSalt(salt),
+                    Err(_) => Response::Error(
+                        ReturnFlags::ServerDbUserSaltNotFound,
+                        "the stored salt is invalid".to_string(),
+                    ),
+                },
+                Err(err) => Response::Error(err, "could not find the salt".to_string()),
             }
         }
-        _ if client_msg.instruction == CommandInst::GetPasswordSalt as i64 => {
+        Request::GetPasswordSalt(username) => {
             use crate::server::db::cmd::get_user_salt::get_user_salt;
-            match get_user_salt(
-                sql_conn,
-                String::from_utf8(client_msg.data).unwrap().as_str(),
-                false,
-                false,
-            )
-            .await
-            {
-                Ok(salt) => {
-                    let server_response: Message = message_builder(
-                        MessageType::DataTransfer,
-                        CommandInst::GetPasswordSalt as i64,
-                        1,
-                        0,
-                        1,
-                        HEXUPPER.decode(salt.as_bytes()).unwrap(),
-                    );
-                    socket
-                        .write_all(bincode::serialize(&server_response).unwrap().as_slice())
-                        .await
-                }
-                Err(_) => {
-                    let server_response =
-                        message_builder(MessageType::ServerReturn, 0, 0, 0, 0, Vec::new());
-
-                    socket
-                        .write_all(bincode::serialize(&server_response).unwrap().as_slice())
-                        .await
-                }
+            match get_user_salt(sql_conn, username.as_str(), false, false).await {
+                Ok(salt) => match HEXUPPER.decode(salt.as_bytes()) {
+                    Ok(salt) => Response::PasswordSalt(salt),
+                    Err(_) => Response::Error(
+                        ReturnFlags::ServerDbUserSaltNotFound,
+                        "the stored salt is invalid".to_string(),
+                    ),
+                },
+                Err(err) => Response::Error(err, "could not find the salt".to_string()),
             }
         }
-        _ if client_msg.instruction == CommandInst::Register as i64 => {
-            register(sql_conn, socket, &client_msg).await
-        }
-        _ if client_msg.instruction == CommandInst::LoginMethod1 as i64 => {
-            login_normal(sql_conn, socket, &client_msg).await
+        Request::Register(register_req) => register(sql_conn, &register_req).await,
+        Request::LoginNormal(login_req) => login_normal(sql_conn, &login_req).await,
+        Request::GetUserPortfolio(auth_jwt) => retrieve_portfolio(sql_conn, auth_jwt).await,
+        Request::GetUserTransactionHist(auth_jwt) => {
+            retrieve_transactions(sql_conn, auth_jwt).await
         }
-        _ if client_msg.instruction == DataTransferInst::GetUserPortfolio as i64 => {
-            retrieve_portfolio(socket, &client_msg).await
-        }
-        _ if client_msg.instruction == DataTransferInst::GetUserTransactionHist as i64 => {
-            retrieve_transactions(sql_conn, socket, &client_msg).await
+        Request::GetAssetData(asset_data_req) => get_asset_data(sql_conn, &asset_data_req).await,
+        Request::PurchaseAsset(purchase_req) => purchase_asset(sql_conn, &purchase_req).await,
+        Request::SellAsset(sell_req) => sell_asset(sql_conn, &sell_req).await,
+        Request::PlaceOrder(order_req) => place_order(sql_conn, &order_req).await,
+        Request::PlaceOrderGroup(group_req) => place_order_group(sql_conn, &group_req).await,
+        Request::GetUserOpenOrders(auth_jwt) => retrieve_orders(sql_conn, auth_jwt, true).await,
+        Request::GetUserOrderHistory(auth_jwt) => retrieve_orders(sql_conn, auth_jwt, false).await,
+        Request::CancelOrder(cancel_req) => cancel_order(sql_conn, &cancel_req).await,
+        Request::AmendOrder(amend_req) => amend_order(sql_conn, &amend_req).await,
+        Request::Ping => Response::Pong,
+        Request::Subscribe(_) | Request::Unsubscribe(_) => {
+            /* subscriptions belong to the connection, see handle_connection() */
+            Response::Error(
+                ReturnFlags::ServerHandleDataRcvdInvMsg,
+                "subscriptions are not available here".to_string(),
+            )
         }
-        _ => Ok(()),
     }
 }
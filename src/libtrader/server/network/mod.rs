@@ -1,4 +1,7 @@
 pub mod cmd;
 pub mod gen_tls_server_config;
+pub mod handle_connection;
 pub mod handle_data;
 pub mod jwt_wrapper;
+pub mod validate_request;
+pub mod websocket_gateway;
@@ -0,0 +1,129 @@
+use log::warn;
+
+use crate::common::message::message::Message;
+use crate::common::message::message_schema::validate_request_message;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::network::jwt_wrapper::verify_jwt_token;
+
+/// Validates a received request before it is handled.
+///
+/// The message must match the ```MessageSchema``` of its instruction and decode to the request
+/// it announces. Requests requiring authorization must carry a valid JWT token.
+///
+/// Arguments:
+/// message - The received message.
+///
+/// Returns: the decoded ```Request``` on success, the ```Response::Error``` to answer on error.
+///
+/// Example:
+/// ```rust
+///     let response = match validate_request(&client_msg) {
+///         Ok(request) => handle_data(&sql_conn, request).await,
+///         Err(response) => response,
+///     };
+/// ```
+pub fn validate_request(message: &Message) -> Result<Request, Response> {
+    let schema = validate_request_message(message).map_err(|flag| {
+        warn!("VALIDATE_REQUEST_INVALID_MESSAGE: {}", flag);
+        Response::Error(
+            flag,
+            format!(
+                "invalid request {} {}",
+                message.msgtype, message.instruction
+            ),
+        )
+    })?;
+
+    let request = Request::from_message(message).map_err(|err| {
+        warn!("VALIDATE_REQUEST_DECODE_FAILED: {}", err);
+        Response::Error(
+            schema.invalid_flag,
+            format!("could not decode the request: {}", err),
+        )
+    })?;
+
+    if schema.requires_auth {
+        let authorized = match request.auth_token() {
+            Some(token) => verify_jwt_token(token.to_string()).is_ok(),
+            None => false,
+        };
+        if !authorized {
+            warn!("VALIDATE_REQUEST_UNAUTHORIZED");
+            return Err(Response::Error(
+                ReturnFlags::ServerAccUnauthorized,
+                "invalid or expired token".to_string(),
+            ));
+        }
+    }
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::message_schema::request_schema;
+    use crate::common::message::request::sample_requests;
+
+    /// Asserts that a malformed request is answered with the error of its schema.
+    fn assert_rejected(message: &Message) {
+        let schema = request_schema(message.msgtype, message.instruction).unwrap();
+        match validate_request(message) {
+            Err(Response::Error(flag, _)) => assert_eq!(flag, schema.invalid_flag),
+            response => panic!("unexpected response {:?}", response),
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let request = Request::GetEmailSalt("n1ckn8me".to_string());
+        assert_eq!(validate_request(&request.to_message()), Ok(request));
+
+        match validate_request(&Request::GetUserPortfolio("jwt".to_string()).to_message()) {
+            Err(Response::Error(flag, _)) => assert_eq!(flag, ReturnFlags::ServerAccUnauthorized),
+            response => panic!("unexpected response {:?}", response),
+        }
+    }
+
+    #[test]
+    fn test_validate_request_malformed() {
+        for request in sample_requests() {
+            let message = request.to_message();
+
+            /* empty, truncated and padded payloads */
+            let mut malformed = message.clone();
+            malformed.data.clear();
+            assert_rejected(&malformed);
+            let mut malformed = message.clone();
+            malformed.data.pop();
+            assert_rejected(&malformed);
+            let mut malformed = message.clone();
+            malformed.data.push(0);
+            assert_rejected(&malformed);
+
+            /* every length prefix forged to claim u64::MAX bytes */
+            let mut malformed = message.clone();
+            for byte in malformed.data.iter_mut().skip(4) {
+                *byte = 0xff;
+            }
+            if malformed.data.len() > 4 {
+                assert_rejected(&malformed);
+            }
+
+            /* unknown variant tag */
+            let mut malformed = message.clone();
+            malformed.data[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert_rejected(&malformed);
+
+            /* payload of another instruction */
+            let mut malformed = message.clone();
+            malformed.data = Request::Ping.to_message().data;
+            if request != Request::Ping && request != Request::GenHashSalt {
+                assert_rejected(&malformed);
+            }
+        }
+    }
+}
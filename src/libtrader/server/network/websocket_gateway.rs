@@ -0,0 +1,342 @@
+use futures::stream::FuturesUnordered;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+use tokio::time::{sleep_until, Instant};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+use crate::common::network::transport::Transport;
+
+use crate::server::network::handle_connection::MAX_IN_FLIGHT_REQUESTS;
+use crate::server::network::validate_request::validate_request;
+
+/// The largest JSON message accepted from a gateway client, in bytes.
+pub static MAX_GATEWAY_MESSAGE_LENGTH: usize = 64 * 1024;
+
+/// A request sent by a gateway client, as a JSON text message.
+///
+/// The request uses the JSON form of ```Request```, byte vectors being arrays of numbers.
+///
+/// Members:
+/// id - Chosen by the client, copied to the response.
+/// request - The request.
+///
+/// Example:
+/// ```json
+///     {"id": 1, "request": {"GetUserPortfolio": "<JWT token>"}}
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GatewayRequest {
+    pub id: u64,
+    pub request: Request,
+}
+
+/// A response sent to a gateway client, as a JSON text message.
+///
+/// Members:
+/// id - The id of the request, ```0``` if the request could not be parsed.
+/// response - The response, errors being ```{"Error": ["<ReturnFlags>", "<detail>"]}```.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GatewayResponse {
+    pub id: u64,
+    pub response: Response,
+}
+
+/// Serves a WebSocket client speaking JSON until it disconnects.
+///
+/// Runs the WebSocket handshake, then translates every ```GatewayRequest``` to the ```Message```
+/// the binary protocol would carry. Requests go through ```validate_request()```, so schemas and
+/// JWT checks are the same as for binary clients, before being passed to ```handler```.
+/// Requests are handled concurrently, up to ```MAX_IN_FLIGHT_REQUESTS``` at once as for binary
+/// clients, responses may be sent out of order. Clients sending nothing for ```idle_timeout```
+/// are disconnected.
+/// Should be used in contexts that return ```io::Result```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// stream - The connection to the client, before the WebSocket handshake.
+/// idle_timeout - The longest time allowed between two messages of the client.
+/// handler - Handles a validated request, ```handle_data()``` outside of tests.
+///
+/// Returns: ```io::Result``` failing if the handshake failed or the connection is unusable.
+///
+/// Example:
+/// ```rust
+///     handle_websocket(socket, idle_timeout, |request| handle_data(&sql_conn, request)).await?;
+/// ```
+pub async fn handle_websocket<T, F, Fut>(
+    stream: T,
+    idle_timeout: Duration,
+    handler: F,
+) -> io::Result<()>
+where
+    T: Transport,
+    F: Fn(Request) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let config = WebSocketConfig {
+        max_message_size: Some(MAX_GATEWAY_MESSAGE_LENGTH),
+        max_frame_size: Some(MAX_GATEWAY_MESSAGE_LENGTH),
+        ..WebSocketConfig::default()
+    };
+    let mut socket = tokio_tungstenite::accept_async_with_config(stream, Some(config))
+        .await
+        .map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("WEBSOCKET_HANDSHAKE_FAILED: {}", err),
+            )
+        })?;
+
+    let mut in_flight = FuturesUnordered::new();
+    let mut last_received = Instant::now();
+
+    loop {
+        tokio::select! {
+            ws_msg = socket.next(), if in_flight.len() < MAX_IN_FLIGHT_REQUESTS => match ws_msg {
+                Some(Ok(WsMessage::Text(text))) => {
+                    last_received = Instant::now();
+                    match parse_request(&text) {
+                        Ok((id, request)) => {
+                            let response = handler(request);
+                            in_flight.push(async move { (id, response.await) });
+                        }
+                        Err(response) => send_response(&mut socket, *response).await?,
+                    }
+                }
+                Some(Ok(WsMessage::Binary(_))) => {
+                    last_received = Instant::now();
+                    let response = GatewayResponse {
+                        id: 0,
+                        response: Response::Error(
+                            ReturnFlags::ServerHandleDataRcvdInvMsg,
+                            "only JSON text messages are accepted".to_string(),
+                        ),
+                    };
+                    send_response(&mut socket, response).await?;
+                }
+                Some(Ok(WsMessage::Close(_))) | None => break,
+                Some(Ok(_)) => {
+                    /* pings are answered by the WebSocket library */
+                    last_received = Instant::now();
+                }
+                Some(Err(err)) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("WEBSOCKET_READ_FAILED: {}", err),
+                    ))
+                }
+            },
+            Some((id, response)) = in_flight.next() => {
+                /* the client is not read at the cap, its silence meanwhile does not count */
+                if in_flight.len() + 1 >= MAX_IN_FLIGHT_REQUESTS {
+                    last_received = Instant::now();
+                }
+                send_response(&mut socket, GatewayResponse { id, response }).await?;
+            }
+            _ = sleep_until(last_received + idle_timeout), if in_flight.len() < MAX_IN_FLIGHT_REQUESTS => {
+                warn!("HANDLE_WEBSOCKET_IDLE_TIMEOUT");
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{}", ReturnFlags::ServerConnIdleTimeout),
+                ));
+            }
+        }
+    }
+
+    /* answer the requests left in flight */
+    while let Some((id, response)) = in_flight.next().await {
+        send_response(&mut socket, GatewayResponse { id, response }).await?;
+    }
+    Ok(())
+}
+
+/// Parses and validates a ```GatewayRequest```.
+///
+/// Returns: the id and the validated request, the ```GatewayResponse``` to send on error.
+fn parse_request(text: &str) -> Result<(u64, Request), Box<GatewayResponse>> {
+    let gateway_req: GatewayRequest = serde_json::from_str(text).map_err(|err| {
+        warn!("HANDLE_WEBSOCKET_INVALID_JSON: {}", err);
+        Box::new(GatewayResponse {
+            id: 0,
+            response: Response::Error(
+                ReturnFlags::ServerHandleDataRcvdInvMsg,
+                format!("invalid request: {}", err),
+            ),
+        })
+    })?;
+
+    let id = gateway_req.id;
+    match validate_request(&gateway_req.request.to_message()) {
+        Ok(request) => Ok((id, request)),
+        Err(response) => Err(Box::new(GatewayResponse { id, response })),
+    }
+}
+
+/// Sends a response as a JSON text message.
+async fn send_response<S>(socket: &mut S, response: GatewayResponse) -> io::Result<()>
+where
+    S: futures::Sink<WsMessage, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    let text = serde_json::to_string(&response).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("WEBSOCKET_ENCODE_FAILED: {}", err),
+        )
+    })?;
+    socket.send(WsMessage::Text(text)).await.map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::BrokenPipe,
+            format!("WEBSOCKET_WRITE_FAILED: {}", err),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::message::request::LoginRequest;
+
+    /// Runs a gateway over a duplex pair, answering every request with ```Response::Pong```.
+    async fn connect() -> tokio_tungstenite::WebSocketStream<tokio::io::DuplexStream> {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(handle_websocket(
+            server,
+            Duration::from_secs(10),
+            |_| async { Response::Pong },
+        ));
+        let (socket, _) = tokio_tungstenite::client_async("ws://localhost/", client)
+            .await
+            .unwrap();
+        socket
+    }
+
+    async fn exchange(
+        socket: &mut tokio_tungstenite::WebSocketStream<tokio::io::DuplexStream>,
+        text: String,
+    ) -> GatewayResponse {
+        socket.send(WsMessage::Text(text)).await.unwrap();
+        match socket.next().await {
+            Some(Ok(WsMessage::Text(text))) => serde_json::from_str(&text).unwrap(),
+            ws_msg => panic!("unexpected message {:?}", ws_msg),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_gateway_requests() {
+        let mut socket = connect().await;
+
+        /* valid requests reach the handler */
+        let login = GatewayRequest {
+            id: 7,
+            request: Request::LoginNormal(LoginRequest {
+                username: "n1ckn8me".to_string(),
+                hashed_email: vec![1u8; 64],
+                hashed_password: vec![2u8; 64],
+            }),
+        };
+        let response = exchange(&mut socket, serde_json::to_string(&login).unwrap()).await;
+        assert_eq!(
+            response,
+            GatewayResponse {
+                id: 7,
+                response: Response::Pong
+            }
+        );
+
+        /* the JWT token is checked before the handler */
+        let response = exchange(
+            &mut socket,
+            r#"{"id": 8, "request": {"GetUserPortfolio": "not a token"}}"#.to_string(),
+        )
+        .await;
+        assert_eq!(response.id, 8);
+        assert!(matches!(
+            response.response,
+            Response::Error(ReturnFlags::ServerAccUnauthorized, _)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_gateway_invalid_json() {
+        let mut socket = connect().await;
+
+        let response = exchange(&mut socket, r#"{"id": 1, "request": "#.to_string()).await;
+        assert_eq!(response.id, 0);
+        assert!(matches!(
+            response.response,
+            Response::Error(ReturnFlags::ServerHandleDataRcvdInvMsg, _)
+        ));
+
+        /* schema violations are reported with the request id */
+        let response = exchange(
+            &mut socket,
+            r#"{"id": 2, "request": {"GetEmailSalt": ""}}"#.to_string(),
+        )
+        .await;
+        assert_eq!(response.id, 2);
+        assert!(matches!(response.response, Response::Error(_, _)));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_gateway_in_flight_cap() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::sync::Semaphore;
+
+        /* requests are held until permits are added */
+        let started = Arc::new(AtomicUsize::new(0));
+        let permits = Arc::new(Semaphore::new(0));
+        let (client, server) = tokio::io::duplex(1024 * 1024);
+        let (handler_started, handler_permits) = (started.clone(), permits.clone());
+        tokio::spawn(handle_websocket(
+            server,
+            Duration::from_secs(10),
+            move |_| {
+                let started = handler_started.clone();
+                let permits = handler_permits.clone();
+                async move {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    permits.acquire().await.unwrap().forget();
+                    Response::Pong
+                }
+            },
+        ));
+        let (mut socket, _) = tokio_tungstenite::client_async("ws://localhost/", client)
+            .await
+            .unwrap();
+
+        let sent = MAX_IN_FLIGHT_REQUESTS + 8;
+        for id in 1..=sent as u64 {
+            let request = GatewayRequest {
+                id,
+                request: Request::Ping,
+            };
+            let text = serde_json::to_string(&request).unwrap();
+            socket.send(WsMessage::Text(text)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(started.load(Ordering::SeqCst), MAX_IN_FLIGHT_REQUESTS);
+
+        /* the requests left are read as the first ones are answered */
+        permits.add_permits(sent);
+        for _ in 0..sent {
+            match socket.next().await {
+                Some(Ok(WsMessage::Text(text))) => {
+                    let response: GatewayResponse = serde_json::from_str(&text).unwrap();
+                    assert_eq!(response.response, Response::Pong);
+                }
+                ws_msg => panic!("unexpected message {:?}", ws_msg),
+            }
+        }
+        assert_eq!(started.load(Ordering::SeqCst), sent);
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::JsonRejection;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::common::message::request::{LoginRequest, RegisterRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::rest::rest_error::RestError;
+use crate::server::rest::rest_request::rest_request;
+
+/// The body answered to a successful ```POST /sessions```.
+///
+/// Members:
+/// token - The JWT token, sent back as ```Authorization: Bearer <token>```.
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug)]
+pub struct SessionToken {
+    pub token: String,
+}
+
+/// Creates an account, see ```acc_create()```.
+///
+/// The hashes and salts are computed by the client, as for ```Request::Register```.
+#[utoipa::path(
+    post,
+    path = "/accounts",
+    tag = "accounts",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "The account was created"),
+        (status = 400, description = "The body is invalid", body = RestError),
+        (status = 409, description = "The username is taken", body = RestError),
+    )
+)]
+pub async fn post_accounts(
+    State(sql_conn): State<Arc<tokio_postgres::Client>>,
+    body: Result<Json<RegisterRequest>, JsonRejection>,
+) -> Result<StatusCode, RestError> {
+    let Json(register_req) =
+        body.map_err(|err| RestError::new(ReturnFlags::ServerRegisterInvMsg, err.body_text()))?;
+
+    match rest_request(&sql_conn, Request::Register(register_req)).await? {
+        Response::Registered => Ok(StatusCode::CREATED),
+        response => Err(RestError::from_response(response)),
+    }
+}
+
+/// Logs in an account, see ```acc_auth()```.
+///
+/// The email and password are hashed with the salts returned at registration, as for
+/// ```Request::LoginNormal```.
+#[utoipa::path(
+    post,
+    path = "/sessions",
+    tag = "accounts",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "The account is logged in", body = SessionToken),
+        (status = 400, description = "The body is invalid", body = RestError),
+        (status = 401, description = "The credentials are wrong", body = RestError),
+        (status = 404, description = "The account does not exist", body = RestError),
+    )
+)]
+pub async fn post_sessions(
+    State(sql_conn): State<Arc<tokio_postgres::Client>>,
+    body: Result<Json<LoginRequest>, JsonRejection>,
+) -> Result<Json<SessionToken>, RestError> {
+    let Json(login_req) =
+        body.map_err(|err| RestError::new(ReturnFlags::ServerLoginInvMsg, err.body_text()))?;
+
+    match rest_request(&sql_conn, Request::LoginNormal(login_req)).await? {
+        Response::LoggedIn(token) => Ok(Json(SessionToken { token })),
+        response => Err(RestError::from_response(response)),
+    }
+}
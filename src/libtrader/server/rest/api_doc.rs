@@ -0,0 +1,93 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::common::account::portfolio::Portfolio;
+use crate::common::account::position::Position;
+use crate::common::account::transaction::{Transaction, TransactionKind};
+use crate::common::generic::company::Company;
+use crate::common::generic::stock_val::StockVal;
+use crate::common::message::request::{LoginRequest, RegisterRequest};
+
+use crate::server::rest::accounts::SessionToken;
+use crate::server::rest::rest_error::RestError;
+
+/// The OpenAPI document of the REST API, served at ```GET /openapi.json```.
+///
+/// Example:
+/// ```rust
+///     let json = ApiDoc::openapi().to_pretty_json()?;
+/// ```
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "PaperTrader REST API"),
+    paths(
+        crate::server::rest::accounts::post_accounts,
+        crate::server::rest::accounts::post_sessions,
+        crate::server::rest::portfolio::get_portfolio,
+        crate::server::rest::portfolio::get_transactions,
+        crate::server::rest::assets::get_asset,
+        crate::server::rest::assets::get_asset_history,
+    ),
+    components(schemas(
+        RegisterRequest,
+        LoginRequest,
+        SessionToken,
+        Portfolio,
+        Position,
+        Transaction,
+        TransactionKind,
+        Company,
+        StockVal,
+        RestError,
+    )),
+    modifiers(&BearerJwt),
+    tags(
+        (name = "accounts", description = "Account creation and login"),
+        (name = "portfolio", description = "Data of the logged in account"),
+        (name = "assets", description = "Companies and stock values"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Declares the ```bearer_jwt``` security scheme, the JWT token returned by ```POST /sessions```.
+struct BearerJwt;
+
+impl Modify for BearerJwt {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_jwt",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_api_doc_paths() {
+        let doc = ApiDoc::openapi();
+        for path in [
+            "/accounts",
+            "/sessions",
+            "/portfolio",
+            "/transactions",
+            "/assets/{symbol}",
+            "/assets/{symbol}/history",
+        ] {
+            assert!(doc.paths.paths.contains_key(path), "missing {}", path);
+        }
+
+        let components = doc.components.unwrap();
+        assert!(components.security_schemes.contains_key("bearer_jwt"));
+        assert!(components.schemas.contains_key("Portfolio"));
+    }
+}
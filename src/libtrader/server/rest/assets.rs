@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use log::warn;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::common::generic::company::Company;
+use crate::common::generic::stock_val::StockVal;
+use crate::common::message::request::{AssetDataRequest, Request};
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::db::cmd::get_company::get_company_from_db;
+use crate::server::rest::rest_error::RestError;
+use crate::server::rest::rest_request::rest_request;
+
+/// The query of ```GET /assets/{symbol}/history```.
+///
+/// Members:
+/// start_epoch - The first time epoch to retrieve.
+/// end_epoch - The last time epoch to retrieve.
+#[derive(Deserialize, IntoParams, PartialEq, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    pub start_epoch: i64,
+    pub end_epoch: i64,
+}
+
+/// Returns the company behind a symbol, see ```get_company_from_db()```.
+#[utoipa::path(
+    get,
+    path = "/assets/{symbol}",
+    tag = "assets",
+    params(("symbol" = String, Path, description = "The symbol of the stock")),
+    responses(
+        (status = 200, description = "The company", body = Company),
+        (status = 404, description = "No company has the symbol", body = RestError),
+    )
+)]
+pub async fn get_asset(
+    State(sql_conn): State<Arc<tokio_postgres::Client>>,
+    Path(symbol): Path<String>,
+) -> Result<Json<Company>, RestError> {
+    match get_company_from_db(&sql_conn, &symbol).await {
+        Ok(company) => Ok(Json(company)),
+        Err(err) => {
+            warn!("GET_ASSET_FAILED: {}", err);
+            Err(RestError::new(err, "could not find the company"))
+        }
+    }
+}
+
+/// Returns the values of a stock between two time epochs, see
+/// ```get_stock_from_db_between_epochs()```.
+#[utoipa::path(
+    get,
+    path = "/assets/{symbol}/history",
+    tag = "assets",
+    params(
+        ("symbol" = String, Path, description = "The symbol of the stock"),
+        HistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "The values of the stock", body = [StockVal]),
+        (status = 400, description = "The query is invalid", body = RestError),
+        (status = 404, description = "The stock does not exist", body = RestError),
+    )
+)]
+pub async fn get_asset_history(
+    State(sql_conn): State<Arc<tokio_postgres::Client>>,
+    Path(symbol): Path<String>,
+    query: Result<Query<HistoryQuery>, QueryRejection>,
+) -> Result<Json<Vec<StockVal>>, RestError> {
+    let Query(query) = query
+        .map_err(|err| RestError::new(ReturnFlags::ServerGetAssetDataInvMsg, err.body_text()))?;
+
+    let request = Request::GetAssetData(AssetDataRequest {
+        symbol,
+        start_epoch: query.start_epoch,
+        end_epoch: query.end_epoch,
+    });
+    match rest_request(&sql_conn, request).await? {
+        Response::AssetData(vals) => Ok(Json(vals)),
+        response => Err(RestError::from_response(response)),
+    }
+}
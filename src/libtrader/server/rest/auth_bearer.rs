@@ -0,0 +1,70 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+
+use crate::common::misc::return_flags::ReturnFlags;
+
+use crate::server::rest::rest_error::RestError;
+
+/// The JWT token of an ```Authorization: Bearer <token>``` header.
+///
+/// Only the presence of the header is checked here, the token itself is verified with the
+/// request it authorizes, see ```rest_request()```.
+///
+/// Example:
+/// ```rust
+///     async fn get_portfolio(AuthBearer(auth_jwt): AuthBearer) { /* ... */ }
+/// ```
+#[derive(PartialEq, Debug)]
+pub struct AuthBearer(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AuthBearer {
+    type Rejection = RestError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| AuthBearer(token.trim().to_string()))
+            .ok_or_else(|| {
+                RestError::new(ReturnFlags::ServerAccUnauthorized, "missing bearer token")
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use axum::http::Request;
+
+    async fn extract(request: Request<()>) -> Result<AuthBearer, RestError> {
+        let (mut parts, _) = request.into_parts();
+        AuthBearer::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_auth_bearer() {
+        let request = Request::builder()
+            .header(AUTHORIZATION, "Bearer abc.def.ghi")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            extract(request).await,
+            Ok(AuthBearer("abc.def.ghi".to_string()))
+        );
+
+        let request = Request::builder()
+            .header(AUTHORIZATION, "Basic dXNlcjpwYXNz")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            extract(request).await.unwrap_err().flag,
+            ReturnFlags::ServerAccUnauthorized
+        );
+        assert!(extract(Request::new(())).await.is_err());
+    }
+}
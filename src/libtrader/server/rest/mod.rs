@@ -0,0 +1,8 @@
+pub mod accounts;
+pub mod api_doc;
+pub mod assets;
+pub mod auth_bearer;
+pub mod portfolio;
+pub mod rest_error;
+pub mod rest_request;
+pub mod router;
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use crate::common::account::portfolio::Portfolio;
+use crate::common::account::transaction::Transaction;
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+
+use crate::server::rest::auth_bearer::AuthBearer;
+use crate::server::rest::rest_error::RestError;
+use crate::server::rest::rest_request::rest_request;
+
+/// Returns the portfolio of the logged in account, see ```acc_retrieve_portfolio()```.
+#[utoipa::path(
+    get,
+    path = "/portfolio",
+    tag = "portfolio",
+    security(("bearer_jwt" = [])),
+    responses(
+        (status = 200, description = "The portfolio of the account", body = Portfolio),
+        (status = 401, description = "The token is missing or expired", body = RestError),
+    )
+)]
+pub async fn get_portfolio(
+    State(sql_conn): State<Arc<tokio_postgres::Client>>,
+    AuthBearer(auth_jwt): AuthBearer,
+) -> Result<Json<Portfolio>, RestError> {
+    match rest_request(&sql_conn, Request::GetUserPortfolio(auth_jwt)).await? {
+        Response::Portfolio(portfolio) => Ok(Json(portfolio)),
+        response => Err(RestError::from_response(response)),
+    }
+}
+
+/// Returns the transactions of the logged in account, see ```acc_retrieve_transaction()```.
+#[utoipa::path(
+    get,
+    path = "/transactions",
+    tag = "portfolio",
+    security(("bearer_jwt" = [])),
+    responses(
+        (status = 200, description = "The transactions of the account", body = [Transaction]),
+        (status = 401, description = "The token is missing or expired", body = RestError),
+    )
+)]
+pub async fn get_transactions(
+    State(sql_conn): State<Arc<tokio_postgres::Client>>,
+    AuthBearer(auth_jwt): AuthBearer,
+) -> Result<Json<Vec<Transaction>>, RestError> {
+    match rest_request(&sql_conn, Request::GetUserTransactionHist(auth_jwt)).await? {
+        Response::Transactions(transactions) => Ok(Json(transactions)),
+        response => Err(RestError::from_response(response)),
+    }
+}
@@ -0,0 +1,116 @@
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::common::message::response::Response;
+use crate::common::misc::return_flags::ReturnFlags;
+
+/// An error answered by the REST API, as a JSON body.
+///
+/// The HTTP status is derived from the flag, see ```RestError::status()```.
+///
+/// Members:
+/// flag - The ```ReturnFlags``` describing the failure, by name.
+/// detail - A detail message meant for humans.
+///
+/// Example:
+/// ```json
+///     {"flag": "ServerAccUnauthorized", "detail": "invalid or expired token"}
+/// ```
+#[derive(Serialize, Deserialize, ToSchema, PartialEq, Debug)]
+pub struct RestError {
+    #[schema(value_type = String, example = "ServerAccUnauthorized")]
+    pub flag: ReturnFlags,
+    pub detail: String,
+}
+
+impl RestError {
+    pub fn new(flag: ReturnFlags, detail: impl Into<String>) -> Self {
+        RestError {
+            flag,
+            detail: detail.into(),
+        }
+    }
+
+    /// Builds the error out of a response that is not the expected one.
+    ///
+    /// Arguments:
+    /// response - The unexpected response, ```Response::Error``` keeps its flag and detail.
+    ///
+    /// Returns: the ```RestError``` to answer.
+    pub fn from_response(response: Response) -> Self {
+        match response {
+            Response::Error(flag, detail) => RestError::new(flag, detail),
+            response => RestError::new(
+                ReturnFlags::ServerHandleDataRcvdInvMsg,
+                format!("unexpected response {}", response),
+            ),
+        }
+    }
+
+    /// Returns the HTTP status answered with the error.
+    pub fn status(&self) -> StatusCode {
+        match self.flag {
+            ReturnFlags::ServerAccUnauthorized => StatusCode::UNAUTHORIZED,
+            ReturnFlags::ServerAccUserExists
+            | ReturnFlags::ServerSellAssetOversold
+            | ReturnFlags::ServerAccInsufficientCash
+            | ReturnFlags::ServerOrderNotOpen => StatusCode::CONFLICT,
+            ReturnFlags::ServerDbSearchStockNotFound
+            | ReturnFlags::ServerDbSearchCompanyNotFound
+            | ReturnFlags::ServerDbUserSaltNotFound
+            | ReturnFlags::ServerDbUserHashNotFound
+            | ReturnFlags::ServerGetUserIdNotFound
+            | ReturnFlags::ServerOrderNotFound => StatusCode::NOT_FOUND,
+            ReturnFlags::ServerRegisterInvMsg
+            | ReturnFlags::ServerLoginInvMsg
+            | ReturnFlags::ServerPurchaseAssetInvMsg
+            | ReturnFlags::ServerSellAssetInvMsg
+            | ReturnFlags::ServerPlaceOrderInvMsg
+            | ReturnFlags::ServerPlaceOrderGroupInvMsg
+            | ReturnFlags::ServerRetrieveOrdersInvMsg
+            | ReturnFlags::ServerCancelOrderInvMsg
+            | ReturnFlags::ServerAmendOrderInvMsg
+            | ReturnFlags::ServerGetAssetDataInvMsg
+            | ReturnFlags::ServerGetAssetInfoInvMsg
+            | ReturnFlags::ServerRetrievePortfolioInvMsg
+            | ReturnFlags::ServerRetrieveTransactionInvMsg => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> axum::response::Response {
+        (self.status(), Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rest_error_status() {
+        let error = RestError::from_response(Response::Error(
+            ReturnFlags::ServerAccUnauthorized,
+            "invalid or expired token".to_string(),
+        ));
+        assert_eq!(error.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"flag":"ServerAccUnauthorized","detail":"invalid or expired token"}"#
+        );
+
+        assert_eq!(
+            RestError::new(ReturnFlags::ServerLoginInvMsg, "").status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            RestError::from_response(Response::Pong).status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use crate::common::message::request::Request;
+use crate::common::message::response::Response;
+
+use crate::server::network::handle_data::handle_data;
+use crate::server::network::validate_request::validate_request;
+use crate::server::rest::rest_error::RestError;
+
+/// Handles a request received through the REST API.
+///
+/// The request is checked by ```validate_request()``` as if it was received as a ```Message```,
+/// so schemas and JWT checks are the same as for binary clients, then passed to
+/// ```handle_data()```.
+/// Should be used in Async contexts.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+/// request - The request built out of the HTTP request.
+///
+/// Returns: the response on success, the ```RestError``` to answer if the request is invalid or
+/// failed.
+///
+/// Example:
+/// ```rust
+///     match rest_request(&sql_conn, Request::GetUserPortfolio(auth_jwt)).await? {
+///         Response::Portfolio(portfolio) => Ok(Json(portfolio)),
+///         response => Err(RestError::from_response(response)),
+///     }
+/// ```
+pub async fn rest_request(
+    sql_conn: &tokio_postgres::Client,
+    request: Request,
+) -> Result<Response, RestError> {
+    let request = validate_request(&request.to_message()).map_err(RestError::from_response)?;
+    match handle_data(sql_conn, request).await {
+        Response::Error(flag, detail) => Err(RestError::new(flag, detail)),
+        response => Ok(response),
+    }
+}
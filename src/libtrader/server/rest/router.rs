@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use utoipa::OpenApi;
+
+use crate::server::rest::accounts::{post_accounts, post_sessions};
+use crate::server::rest::api_doc::ApiDoc;
+use crate::server::rest::assets::{get_asset, get_asset_history};
+use crate::server::rest::portfolio::{get_portfolio, get_transactions};
+
+/// Builds the routes of the REST API.
+///
+/// Every route is described in ```ApiDoc```, which is served at ```GET /openapi.json```.
+///
+/// Arguments:
+/// sql_conn - The SQL connection to use.
+///
+/// Returns: the router, ready to be served.
+///
+/// Example:
+/// ```rust
+///     axum::serve(listener, rest_router(sql_conn)).await?;
+/// ```
+pub fn rest_router(sql_conn: Arc<tokio_postgres::Client>) -> Router {
+    Router::new()
+        .route("/accounts", post(post_accounts))
+        .route("/sessions", post(post_sessions))
+        .route("/portfolio", get(get_portfolio))
+        .route("/transactions", get(get_transactions))
+        .route("/assets/:symbol", get(get_asset))
+        .route("/assets/:symbol/history", get(get_asset_history))
+        .route("/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .with_state(sql_conn)
+}